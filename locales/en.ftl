@@ -0,0 +1,311 @@
+## Slash commands
+cmd-server-desc = Register to the Minecraft server allowlist
+cmd-restart-desc = Restart the Minecraft server
+
+## /server
+server-modal-title = Server Registration
+server-modal-label = Game ID
+server-modal-placeholder = Enter your Game ID
+server-empty-game-id = ❌ Please enter a Game ID.
+server-added = ✅ `{ $gamertag }` has been added to the whitelist!
+server-command-failed = ❌ Failed to send command to server.
+
+## /restart
+restart-in-progress = 🔄 Restarting server...
+
+## Status
+status-title = Minecraft Bedrock Server Status
+status-online = **{ status-title }**
+    Server IP: `{ $ip }`
+    Port: `{ $port }`
+    Status: 🟢 Online
+    Players: { $players }/{ $max }{ $player_list }
+    Last Updated: { $time }
+status-offline = **{ status-title }**
+    Server IP: `{ $ip }`
+    Port: `{ $port }`
+    Status: 🔴 Offline
+    Last Updated: { $time }
+status-restart-pending = 🔄 Restart scheduled { $time }
+
+## Admin console
+console-not-authorized = ❌ You are not allowed to run server commands.
+console-confirm = ⚠️ `{ $command }` is a dangerous command. Send `{ $word }` within { $seconds } seconds to run it.
+console-nothing-to-confirm = ❌ There is no command waiting for confirmation (it may have expired).
+console-no-output = (no output)
+console-failed = ❌ Failed to send command: { $error }
+console-single-line = ❌ Send one command per message.
+
+## Chat bridge
+bridge-death = 💀 { $message }
+
+## Players
+cmd-players-desc = Show the players who are online
+players-title = 👥 Online players ({ $count })
+players-empty = Nobody is online right now.
+players-entry = • { $name } (online for { $duration })
+player-joined = 📥 **{ $player }** joined the game
+player-left = 📤 **{ $player }** left the game
+
+## Playtime
+cmd-playtime-desc = Show a player's playtime
+cmd-leaderboard-desc = Show the playtime leaderboard
+option-player = Player name (gamertag)
+option-period = Period
+period-day = Last 24 hours
+period-week = Last 7 days
+period-month = Last 30 days
+period-all = All time
+playtime-specify-player = ❌ Please specify a player.
+playtime-not-found = ❌ No playtime recorded for `{ $player }`.
+playtime-title = ⏱️ Playtime of **{ $player }**
+playtime-line = { $period }: { $duration }
+leaderboard-title = 🏆 Playtime leaderboard ({ $period })
+leaderboard-entry = { $rank }. { $name } — { $duration }
+leaderboard-empty = No playtime recorded yet.
+
+## Common
+not-admin = ❌ You don't have permission to use this command.
+
+## Allowlist pruning
+cmd-allowlist-desc = Manage the allowlist
+cmd-allowlist-prune-desc = Remove players who haven't connected for a long time from the allowlist
+option-days = Remove players who haven't connected for this many days
+option-dry-run = Only list the entries without removing them
+prune-none = ✅ No players have been inactive for { $days } days or more.
+prune-dry-run-title = 🔍 { $count } players inactive for { $days }+ days (dry run, nothing was removed)
+prune-done-title = 🧹 Removed { $count } players inactive for { $days }+ days from the allowlist
+prune-entry = • { $name } — last seen { $last_seen }
+prune-failed = ❌ Failed to prune the allowlist: { $error }
+prune-dm = Your gamertag `{ $gamertag }` was removed from the server allowlist because it hasn't connected for { $days } days. Use /server to register again if you want to come back.
+
+## Guest passes
+cmd-guest-desc = Add a player to the allowlist for a limited time
+option-gamertag = Gamertag
+option-duration = Duration (e.g. 2d, 48h, 30m)
+guest-added = ✅ `{ $gamertag }` is allowlisted until { $expires }.
+guest-invalid-duration = ❌ Could not read the duration `{ $duration }`. Use something like `2d`, `48h` or `30m`.
+guest-failed = ❌ Failed to add the guest: { $error }
+guest-kick-reason = Your guest pass has expired
+
+## Scheduled restarts
+option-restart-delay = Restart after this many minutes (with an in-game countdown)
+option-restart-cancel = Cancel the scheduled restart
+restart-scheduled = ⏰ Server restart scheduled ({ $time }).
+restart-delay-too-long = ❌ The delay can be at most { $max }.
+restart-cancelled = ✅ The scheduled restart was cancelled.
+restart-nothing-to-cancel = ❌ There is no scheduled restart.
+restart-warning-minutes = §eThe server will restart in { $minutes } minute(s)
+restart-warning-seconds = §cThe server will restart in { $seconds } seconds
+
+## Backups
+cmd-backup-desc = Manage world backups
+cmd-backup-now-desc = Back up the world now
+backup-created = 💾 Backup `{ $id }` created ({ $size }).
+backup-failed = ❌ Backup failed: { $error }
+cmd-backup-list-desc = List the backups
+cmd-backup-restore-desc = Stop the server, restore a backup and start it again
+option-backup-id = Backup ID (shown by /backup list)
+backup-list-title = 💾 { $count } backups
+backup-list-entry = • `{ $id }` — { $time } ({ $size })
+backup-list-empty = There are no backups yet.
+backup-restored = ✅ Restored backup `{ $id }` ({ $worlds }) and restarted the server. The previous world was kept as `<name>.pre-restore-<time>`.
+backup-restored-stopped = ✅ Restored backup `{ $id }` ({ $worlds }). The server was left stopped. The previous world was kept as `<name>.pre-restore-<time>`.
+backup-restore-failed = ❌ Failed to restore backup `{ $id }`: { $error }
+
+## Lifecycle hooks
+restart-failed = ❌ The restart was aborted: { $error }
+restart-aborted-announce = §cThe scheduled restart was cancelled
+
+## server.properties
+cmd-config-desc = View or change server.properties
+cmd-config-get-desc = Show a setting (all settings if no key is given)
+cmd-config-set-desc = Change a setting
+option-config-key = Key (e.g. difficulty, max-players)
+option-config-value = New value
+config-title = ⚙️ server.properties
+config-value = `{ $key }` = `{ $value }`
+config-not-found = ❌ `{ $key }` is not in server.properties.
+config-updated = ✅ `{ $key }`: `{ $previous }` → `{ $value }`
+config-restart-needed = ⚠️ The server must be restarted for this change to take effect. Use /restart when ready.
+config-failed = ❌ { $error }
+config-unknown-key = ❌ `{ $key }` is not a known setting and is not in server.properties.
+config-invalid-single-line = ❌ The value must be a single line.
+config-invalid-bool = ❌ `{ $key }` must be true or false.
+config-invalid-range = ❌ `{ $key }` must be an integer between { $min } and { $max }.
+config-invalid-choice = ❌ `{ $key }` must be one of: { $choices }
+
+## Operators
+cmd-op-desc = Set a player's permission level (operator by default)
+cmd-deop-desc = Remove a player's permission entry (back to the default level)
+cmd-ops-desc = List the entries in permissions.json
+option-linked-user = Discord user who registered with /server (instead of a gamertag)
+option-permission-level = Permission level
+permission-visitor = Visitor
+permission-member = Member
+permission-operator = Operator
+op-specify-player = ❌ Specify a gamertag or a Discord user registered with /server.
+op-xuid-unknown = ❌ The XUID of `{ $gamertag }` is unknown. The player needs to join the server once first.
+op-updated = ✅ `{ $gamertag }` is now { $level }.
+deop-done = ✅ Removed the permission entry of `{ $gamertag }`.
+op-next-start = ℹ️ This will take effect the next time the server starts.
+op-failed = ❌ Failed to update permissions.json: { $error }
+ops-title = 🛡️ permissions.json ({ $count } entries)
+ops-entry = • { $name } (`{ $xuid }`) — { $level }
+ops-empty = permissions.json has no entries.
+
+## Moderation
+cmd-kick-desc = Kick a player from the server
+cmd-ban-desc = Ban a player: kick, remove from the allowlist and refuse /server registrations
+cmd-unban-desc = Lift a ban
+option-reason = Reason
+option-ban-duration = Ban duration (e.g. 7d, 12h; permanent if omitted)
+kick-done = 👢 Kicked `{ $gamertag }`.
+ban-done = 🔨 Banned `{ $gamertag }` (until { $until }).
+ban-permanent = permanent
+ban-already = ❌ `{ $gamertag }` is banned (until { $until }).
+unban-done = ✅ Lifted the ban of `{ $gamertag }`.
+unban-not-found = ❌ `{ $gamertag }` is not banned.
+moderation-failed = ❌ { $error }
+server-banned = ❌ You cannot register because you are banned (until { $until }).
+
+## Audit log
+cmd-audit-desc = Audit log of admin actions
+cmd-audit-search-desc = Search the audit log (newest first)
+option-audit-user = Only actions by this user
+option-audit-command = Only this command (e.g. ban, backup restore)
+option-audit-text = Text contained in the arguments or result
+option-audit-limit = Maximum number of entries
+audit-entry = { $icon } { $actor } `/{ $command }` { $arguments } → { $result }
+audit-actor-bot = (bot)
+audit-search-title = 📝 { $count } audit entries
+audit-search-entry = { $time } { $icon } { $actor } `/{ $command }` { $arguments } → { $result }
+audit-search-empty = No matching audit entries.
+audit-search-failed = ❌ Failed to read the audit log: { $error }
+
+## Rate limits
+rate-limited = ⏳ You're doing that too often. Try again { $time }.
+
+## Confirmation
+confirm-prompt = ⚠️ Run { $command }? This cannot be undone. The buttons expire { $expires }.
+confirm-button = Confirm
+cancel-button = Cancel
+confirm-running = ⏳ Running…
+confirm-cancelled = Cancelled.
+confirm-expired = ⌛ This confirmation has expired. Run the command again.
+confirm-not-yours = ❌ Only the user who ran the command can use these buttons.
+
+## /start and /stop
+cmd-start-desc = Start the Minecraft server
+cmd-stop-desc = Stop the Minecraft server
+start-already-running = The server is already running.
+start-in-progress = ⏳ Starting the server...
+start-done = 🟢 The server is up (took { $duration }).
+start-failed = ❌ Failed to start the server: { $error }
+stop-not-running = The server is not running.
+stop-in-progress = ⏳ Stopping the server...
+stop-done = 🔴 The server has stopped.
+stop-failed = ❌ Failed to stop the server: { $error }
+server-state-starting = 🟡 Starting... loading the world.
+server-state-running = 🟢 Running. Finishing post-start hooks...
+server-state-stopping = 🟠 Stopping... saving the world.
+server-state-stopped = 🔴 Stopped.
+
+## Maintenance
+cmd-maintenance-desc = Maintenance mode
+cmd-maintenance-on-desc = Close the server for maintenance
+cmd-maintenance-off-desc = End maintenance and apply queued registrations
+option-maintenance-message = Reason shown in the status and in game
+option-maintenance-eta = Expected duration (e.g. 30m, 2h)
+option-maintenance-mode = How to close the server (default: stop)
+maintenance-mode-stop = Stop the server
+maintenance-mode-lock = Keep running, operators only
+status-maintenance = **{ status-title }**
+    Server IP: `{ $ip }`
+    Port: `{ $port }`
+    Status: 🟡 Maintenance
+    Reason: { $reason }
+    Back: { $eta }
+    Last Updated: { $time }
+maintenance-starting = ⏳ Starting maintenance...
+maintenance-on = 🟡 Maintenance started ({ $mode }). Registrations with /server will be queued until it ends.
+maintenance-already-on = Maintenance is already in progress.
+maintenance-ending = ⏳ Ending maintenance...
+maintenance-off = 🟢 Maintenance ended. Applied { $applied }/{ $queued } queued registrations.
+maintenance-not-active = Maintenance is not in progress.
+maintenance-failed = ❌ Maintenance could not be changed: { $error }
+maintenance-refused = ❌ The server is stopped for maintenance. End it with /maintenance off.
+server-queued = 🟡 The server is under maintenance. **{ $gamertag }** will be added when it ends (expected { $eta }); you'll get a DM.
+
+## Server updates
+cmd-update-desc = Update the Bedrock Dedicated Server
+cmd-update-check-desc = Compare the running version with the latest release
+cmd-update-install-desc = Back up, install a new server version and restart
+option-update-source = URL or local path of the server zip (default: latest release)
+update-versions = Running: `{ $current }` / Latest: `{ $latest }`
+update-available = ⬆️ An update is available. Install it with /update install.
+update-up-to-date = ✅ The server is up to date.
+update-current-unknown = The server is not running, so its version is unknown.
+update-check-failed = ❌ Failed to check for updates: { $error }
+update-in-progress = ⏳ Updating the server (backup, stop, install, start)...
+update-done = ✅ Updated to { $version } ({ $files } files replaced). Previous files: `{ $backup }`
+update-failed = ❌ The update failed: { $error }
+
+## Add-ons
+cmd-addon-desc = Manage behavior packs and resource packs
+cmd-addon-install-desc = Install a .mcpack or .mcaddon and enable it in the world
+cmd-addon-list-desc = List the packs enabled in the world
+cmd-addon-remove-desc = Disable and delete a pack
+option-addon-file = The .mcpack or .mcaddon file
+option-addon-pack = Pack name or UUID
+addon-kind-behavior = Behavior
+addon-kind-resource = Resource
+addon-installed = 📦 Installed { $count } pack(s):
+addon-restart-needed = Restart the server to apply the change.
+addon-install-failed = ❌ Failed to install the add-on: { $error }
+upload-too-large = ❌ `{ $file }` is too large ({ $size }). Files up to { $max } are accepted.
+addon-list-title = 📦 Packs in the world ({ $count }):
+addon-list-empty = No packs are enabled in the world.
+addon-list-entry = • **{ $name }** { $version } ({ $kind }) `{ $uuid }`
+addon-list-entry-missing = • `{ $uuid }` { $version } ({ $kind }) ⚠️ pack folder not found
+addon-removed = 🗑️ Removed **{ $pack }**.
+addon-not-found = No pack named **{ $pack }** is enabled in the world.
+addon-failed = ❌ Add-on operation failed: { $error }
+
+## Worlds
+cmd-world-desc = Manage the worlds in the worlds folder
+cmd-world-list-desc = List the worlds
+cmd-world-switch-desc = Switch level-name to another world and restart the server
+cmd-world-upload-desc = Add a .mcworld file as a new world
+option-world-name = World folder name
+option-world-file = The .mcworld file
+option-world-new-name = Folder name for the new world (default: the world's name)
+world-list-title = 🗺️ Worlds ({ $count }):
+world-list-empty = No worlds were found.
+world-list-entry = • **{ $name }** { $size }
+world-active = ✅ current
+world-switching = ⏳ Switching to **{ $name }**...
+world-switched = ✅ Switched from **{ $previous }** to **{ $name }** and restarted the server.
+world-switched-stopped = ✅ Switched from **{ $previous }** to **{ $name }**. It will be loaded the next time the server starts.
+world-switch-failed = ❌ Failed to switch the world: { $error }
+world-uploaded = ✅ Added the world **{ $name }** ({ $size }). Load it with /world switch.
+world-upload-failed = ❌ Failed to add the world: { $error }
+world-failed = ❌ World operation failed: { $error }
+
+## Resource usage
+status-resources = 📈 CPU: { $cpu } / Memory: { $memory }
+cmd-stats-desc = Show the server's state, uptime, CPU and memory usage
+stats-title = 📊 **Server stats**
+stats-state = State: { $state }
+stats-state-stopped = 🔴 Stopped
+stats-state-starting = 🟡 Starting
+stats-state-running = 🟢 Running
+stats-state-stopping = 🟠 Stopping
+stats-uptime = Uptime: { $uptime }
+stats-players = Players online: { $players }
+stats-cpu = CPU: { $cpu }
+stats-memory = Memory: { $memory }
+stats-unavailable = CPU and memory usage are not available (the server is stopped or the bot is not running on Linux).
+memory-alert = ⚠️ bedrock_server is using { $memory } of memory (limit { $limit }).
+memory-restart-scheduled = 🔄 A restart has been scheduled { $time }.
+memory-restart-pending = 🔄 A restart is already scheduled { $time }.
@@ -0,0 +1,311 @@
+## スラッシュコマンド
+cmd-server-desc = Minecraftサーバーのallowlistに登録する
+cmd-restart-desc = Minecraftサーバーを再起動する
+
+## /server
+server-modal-title = サーバー登録
+server-modal-label = ゲームID
+server-modal-placeholder = ゲームIDを入力してください
+server-empty-game-id = ❌ ゲームIDを入力してください。
+server-added = ✅ `{ $gamertag }` をホワイトリストに追加しました！
+server-command-failed = ❌ サーバーへのコマンド送信に失敗しました。
+
+## /restart
+restart-in-progress = 🔄 サーバーを再起動しています...
+
+## ステータス
+status-title = Minecraft Bedrock サーバー状態
+status-online = **{ status-title }**
+    サーバーIP: `{ $ip }`
+    ポート: `{ $port }`
+    サーバー状態: 🟢 オンライン
+    プレイヤー数: { $players }/{ $max }{ $player_list }
+    最終更新: { $time }
+status-offline = **{ status-title }**
+    サーバーIP: `{ $ip }`
+    ポート: `{ $port }`
+    サーバー状態: 🔴 オフライン
+    最終更新: { $time }
+status-restart-pending = 🔄 再起動予定: { $time }
+
+## 管理用コンソール
+console-not-authorized = ❌ サーバーコマンドを実行する権限がありません。
+console-confirm = ⚠️ `{ $command }` は危険なコマンドです。実行するには { $seconds } 秒以内に `{ $word }` と送信してください。
+console-nothing-to-confirm = ❌ 確認待ちのコマンドはありません（期限切れの可能性があります）。
+console-no-output = （出力なし）
+console-failed = ❌ コマンドを送信できませんでした: { $error }
+console-single-line = ❌ コマンドは1つのメッセージに1つずつ送信してください。
+
+## チャットブリッジ
+bridge-death = 💀 { $message }
+
+## プレイヤー
+cmd-players-desc = オンラインのプレイヤーを表示する
+players-title = 👥 オンラインのプレイヤー ({ $count }人)
+players-empty = 現在オンラインのプレイヤーはいません。
+players-entry = • { $name } （{ $duration } 前から）
+player-joined = 📥 **{ $player }** がゲームに参加しました
+player-left = 📤 **{ $player }** がゲームから退出しました
+
+## プレイ時間
+cmd-playtime-desc = プレイヤーのプレイ時間を表示する
+cmd-leaderboard-desc = プレイ時間のランキングを表示する
+option-player = プレイヤー名（ゲーマータグ）
+option-period = 集計期間
+period-day = 直近24時間
+period-week = 直近7日間
+period-month = 直近30日間
+period-all = 全期間
+playtime-specify-player = ❌ プレイヤー名を指定してください。
+playtime-not-found = ❌ `{ $player }` のプレイ記録はありません。
+playtime-title = ⏱️ **{ $player }** のプレイ時間
+playtime-line = { $period }: { $duration }
+leaderboard-title = 🏆 プレイ時間ランキング（{ $period }）
+leaderboard-entry = { $rank }. { $name } — { $duration }
+leaderboard-empty = まだプレイ記録がありません。
+
+## 共通
+not-admin = ❌ このコマンドを実行する権限がありません。
+
+## allowlist の整理
+cmd-allowlist-desc = allowlistを管理する
+cmd-allowlist-prune-desc = 長期間接続していないプレイヤーをallowlistから削除する
+option-days = 何日間接続していないプレイヤーを対象にするか
+option-dry-run = 削除せずに対象を表示するだけにする
+prune-none = ✅ { $days } 日以上接続していないプレイヤーはいません。
+prune-dry-run-title = 🔍 { $days } 日以上接続していないプレイヤー: { $count } 人（確認のみ、削除していません）
+prune-done-title = 🧹 { $days } 日以上接続していない { $count } 人をallowlistから削除しました
+prune-entry = • { $name } — 最終接続 { $last_seen }
+prune-failed = ❌ allowlistの整理に失敗しました: { $error }
+prune-dm = ゲーマータグ `{ $gamertag }` は { $days } 日以上サーバーに接続していないため、allowlistから削除されました。また遊ぶ場合は /server で再登録してください。
+
+## ゲストパス
+cmd-guest-desc = 期限付きでプレイヤーをallowlistに追加する
+option-gamertag = ゲーマータグ
+option-duration = 期間（例: 2d, 48h, 30m）
+guest-added = ✅ `{ $gamertag }` を { $expires } までallowlistに追加しました。
+guest-invalid-duration = ❌ 期間 `{ $duration }` を読み取れません。`2d` `48h` `30m` のように指定してください。
+guest-failed = ❌ ゲストを追加できませんでした: { $error }
+guest-kick-reason = ゲストパスの期限が切れました
+
+## 再起動の予約
+option-restart-delay = 指定した分数後に再起動する（ゲーム内でカウントダウンを告知します）
+option-restart-cancel = 予約中の再起動を取り消す
+restart-scheduled = ⏰ サーバーの再起動を予約しました（{ $time }）。
+restart-delay-too-long = ❌ 遅延は最大 { $max } までです。
+restart-cancelled = ✅ 予約していた再起動を取り消しました。
+restart-nothing-to-cancel = ❌ 予約中の再起動はありません。
+restart-warning-minutes = §e{ $minutes }分後にサーバーを再起動します
+restart-warning-seconds = §c{ $seconds }秒後にサーバーを再起動します
+
+## バックアップ
+cmd-backup-desc = ワールドのバックアップを管理します
+cmd-backup-now-desc = 今すぐワールドをバックアップします
+backup-created = 💾 バックアップ `{ $id }` を作成しました ({ $size })。
+backup-failed = ❌ バックアップに失敗しました: { $error }
+cmd-backup-list-desc = バックアップの一覧を表示します
+cmd-backup-restore-desc = サーバーを止めてバックアップを復元し、再び起動します
+option-backup-id = バックアップのID (/backup list で確認できます)
+backup-list-title = 💾 バックアップ ({ $count } 個)
+backup-list-entry = • `{ $id }` — { $time } ({ $size })
+backup-list-empty = バックアップはまだありません。
+backup-restored = ✅ バックアップ `{ $id }` ({ $worlds }) を復元し、サーバーを再起動しました。復元前のワールドは `<ワールド名>.pre-restore-<日時>` として残しています。
+backup-restored-stopped = ✅ バックアップ `{ $id }` ({ $worlds }) を復元しました。サーバーは停止したままです。復元前のワールドは `<ワールド名>.pre-restore-<日時>` として残しています。
+backup-restore-failed = ❌ バックアップ `{ $id }` の復元に失敗しました: { $error }
+
+## ライフサイクルフック
+restart-failed = ❌ 再起動を中止しました: { $error }
+restart-aborted-announce = §c予定されていた再起動は中止されました
+
+## server.properties
+cmd-config-desc = server.properties を表示・変更します
+cmd-config-get-desc = 設定を表示します (キーを省略するとすべて表示)
+cmd-config-set-desc = 設定を変更します
+option-config-key = キー (例: difficulty, max-players)
+option-config-value = 新しい値
+config-title = ⚙️ server.properties
+config-value = `{ $key }` = `{ $value }`
+config-not-found = ❌ `{ $key }` は server.properties にありません。
+config-updated = ✅ `{ $key }`: `{ $previous }` → `{ $value }`
+config-restart-needed = ⚠️ この変更を反映するにはサーバーの再起動が必要です。準備ができたら /restart を実行してください。
+config-failed = ❌ { $error }
+config-unknown-key = ❌ `{ $key }` は既知の設定ではなく、server.properties にもありません。
+config-invalid-single-line = ❌ 値は1行で指定してください。
+config-invalid-bool = ❌ `{ $key }` には true か false を指定してください。
+config-invalid-range = ❌ `{ $key }` には { $min } から { $max } までの整数を指定してください。
+config-invalid-choice = ❌ `{ $key }` には次のいずれかを指定してください: { $choices }
+
+## オペレーター
+cmd-op-desc = プレイヤーの権限を設定します (省略時はオペレーター)
+cmd-deop-desc = プレイヤーの権限の設定を削除します (既定の権限に戻ります)
+cmd-ops-desc = permissions.json の内容を表示します
+option-linked-user = /server で登録したDiscordユーザー (ゲーマータグの代わり)
+option-permission-level = 権限
+permission-visitor = 訪問者
+permission-member = メンバー
+permission-operator = オペレーター
+op-specify-player = ❌ ゲーマータグか、/server で登録したDiscordユーザーを指定してください。
+op-xuid-unknown = ❌ `{ $gamertag }` のXUIDが分かりません。一度サーバーに参加してもらう必要があります。
+op-updated = ✅ `{ $gamertag }` を{ $level }にしました。
+deop-done = ✅ `{ $gamertag }` の権限の設定を削除しました。
+op-next-start = ℹ️ 次にサーバーを起動したときに反映されます。
+op-failed = ❌ permissions.json の更新に失敗しました: { $error }
+ops-title = 🛡️ permissions.json ({ $count } 件)
+ops-entry = • { $name } (`{ $xuid }`) — { $level }
+ops-empty = permissions.json には何も登録されていません。
+
+## モデレーション
+cmd-kick-desc = プレイヤーをサーバーからキックします
+cmd-ban-desc = プレイヤーをBANします (キックしてallowlistから削除し、/server での登録を拒否)
+cmd-unban-desc = BANを解除します
+option-reason = 理由
+option-ban-duration = BANの期間 (例: 7d, 12h。省略すると無期限)
+kick-done = 👢 `{ $gamertag }` をキックしました。
+ban-done = 🔨 `{ $gamertag }` をBANしました (期限: { $until })。
+ban-permanent = 無期限
+ban-already = ❌ `{ $gamertag }` はBANされています (期限: { $until })。
+unban-done = ✅ `{ $gamertag }` のBANを解除しました。
+unban-not-found = ❌ `{ $gamertag }` はBANされていません。
+moderation-failed = ❌ { $error }
+server-banned = ❌ BANされているため登録できません (期限: { $until })。
+
+## 監査ログ
+cmd-audit-desc = 管理操作の監査ログ
+cmd-audit-search-desc = 監査ログを検索します (新しい順)
+option-audit-user = このユーザーの操作だけ
+option-audit-command = このコマンドだけ (例: ban, backup restore)
+option-audit-text = 引数か結果に含まれる文字列
+option-audit-limit = 表示する最大件数
+audit-entry = { $icon } { $actor } `/{ $command }` { $arguments } → { $result }
+audit-actor-bot = (ボット)
+audit-search-title = 📝 監査ログ ({ $count } 件)
+audit-search-entry = { $time } { $icon } { $actor } `/{ $command }` { $arguments } → { $result }
+audit-search-empty = 一致する記録はありません。
+audit-search-failed = ❌ 監査ログを読み込めませんでした: { $error }
+
+## レート制限
+rate-limited = ⏳ 操作が多すぎます。{ $time }にもう一度お試しください。
+
+## 確認
+confirm-prompt = ⚠️ { $command } を実行しますか？元に戻せません。ボタンは{ $expires }に無効になります。
+confirm-button = 実行する
+cancel-button = キャンセル
+confirm-running = ⏳ 実行中…
+confirm-cancelled = キャンセルしました。
+confirm-expired = ⌛ 確認の期限が切れました。もう一度コマンドを実行してください。
+confirm-not-yours = ❌ このボタンはコマンドを実行した本人しか使えません。
+
+## /start と /stop
+cmd-start-desc = Minecraftサーバーを起動します
+cmd-stop-desc = Minecraftサーバーを停止します
+start-already-running = サーバーはすでに起動しています。
+start-in-progress = ⏳ サーバーを起動しています...
+start-done = 🟢 サーバーが起動しました（{ $duration }）。
+start-failed = ❌ サーバーを起動できませんでした: { $error }
+stop-not-running = サーバーは起動していません。
+stop-in-progress = ⏳ サーバーを停止しています...
+stop-done = 🔴 サーバーを停止しました。
+stop-failed = ❌ サーバーを停止できませんでした: { $error }
+server-state-starting = 🟡 起動中... ワールドを読み込んでいます。
+server-state-running = 🟢 起動しました。起動後のフックを実行しています...
+server-state-stopping = 🟠 停止中... ワールドを保存しています。
+server-state-stopped = 🔴 停止しました。
+
+## メンテナンス
+cmd-maintenance-desc = メンテナンスモード
+cmd-maintenance-on-desc = メンテナンスのためにサーバーを閉じます
+cmd-maintenance-off-desc = メンテナンスを終了し、待っていた登録を反映します
+option-maintenance-message = ステータスとゲーム内に表示する理由
+option-maintenance-eta = 終了までの目安（例: 30m, 2h）
+option-maintenance-mode = サーバーの閉じ方（既定: 停止）
+maintenance-mode-stop = サーバーを停止する
+maintenance-mode-lock = 動かしたままオペレーターのみにする
+status-maintenance = **{ status-title }**
+    サーバーIP: `{ $ip }`
+    ポート: `{ $port }`
+    サーバー状態: 🟡 メンテナンス中
+    理由: { $reason }
+    終了予定: { $eta }
+    最終更新: { $time }
+maintenance-starting = ⏳ メンテナンスを開始しています...
+maintenance-on = 🟡 メンテナンスを開始しました（{ $mode }）。終了するまで /server の登録は保留されます。
+maintenance-already-on = すでにメンテナンス中です。
+maintenance-ending = ⏳ メンテナンスを終了しています...
+maintenance-off = 🟢 メンテナンスを終了しました。保留中の登録 { $queued } 件のうち { $applied } 件を反映しました。
+maintenance-not-active = メンテナンス中ではありません。
+maintenance-failed = ❌ メンテナンスを切り替えられませんでした: { $error }
+maintenance-refused = ❌ メンテナンスのためサーバーを停止しています。/maintenance off で終了してください。
+server-queued = 🟡 サーバーはメンテナンス中です。**{ $gamertag }** は終了後（予定: { $eta }）に追加され、DMでお知らせします。
+
+## サーバーの更新
+cmd-update-desc = Bedrock Dedicated Server を更新します
+cmd-update-check-desc = 動作中のバージョンと最新版を比べます
+cmd-update-install-desc = バックアップを取り、新しいサーバーを入れて再起動します
+option-update-source = サーバーの zip のURLかパス（既定: 最新版）
+update-versions = 動作中: `{ $current }` / 最新版: `{ $latest }`
+update-available = ⬆️ 更新があります。/update install で更新できます。
+update-up-to-date = ✅ サーバーは最新です。
+update-current-unknown = サーバーが停止しているため、動作中のバージョンは分かりません。
+update-check-failed = ❌ 更新を確認できませんでした: { $error }
+update-in-progress = ⏳ サーバーを更新しています（バックアップ・停止・展開・起動）...
+update-done = ✅ { $version } に更新しました（{ $files } ファイルを置き換え）。以前のファイル: `{ $backup }`
+update-failed = ❌ 更新に失敗しました: { $error }
+
+## Add-ons
+cmd-addon-desc = ビヘイビアーパック・リソースパックを管理します
+cmd-addon-install-desc = .mcpack / .mcaddon をインストールしてワールドで有効にします
+cmd-addon-list-desc = ワールドで有効なパックを一覧表示します
+cmd-addon-remove-desc = パックを無効にして削除します
+option-addon-file = .mcpack または .mcaddon ファイル
+option-addon-pack = パック名または UUID
+addon-kind-behavior = ビヘイビアー
+addon-kind-resource = リソース
+addon-installed = 📦 { $count } 個のパックをインストールしました:
+addon-restart-needed = 反映するにはサーバーを再起動してください。
+addon-install-failed = ❌ アドオンをインストールできませんでした: { $error }
+upload-too-large = ❌ `{ $file }` は大きすぎます（{ $size }）。{ $max } までのファイルを受け付けます。
+addon-list-title = 📦 ワールドのパック ({ $count } 個):
+addon-list-empty = ワールドで有効なパックはありません。
+addon-list-entry = • **{ $name }** { $version } ({ $kind }) `{ $uuid }`
+addon-list-entry-missing = • `{ $uuid }` { $version } ({ $kind }) ⚠️ パックのフォルダがありません
+addon-removed = 🗑️ **{ $pack }** を削除しました。
+addon-not-found = **{ $pack }** というパックはワールドで有効になっていません。
+addon-failed = ❌ アドオンの操作に失敗しました: { $error }
+
+## Worlds
+cmd-world-desc = worlds フォルダのワールドを管理します
+cmd-world-list-desc = ワールドを一覧表示します
+cmd-world-switch-desc = level-name を別のワールドに切り替えてサーバーを再起動します
+cmd-world-upload-desc = .mcworld ファイルを新しいワールドとして追加します
+option-world-name = ワールドのフォルダ名
+option-world-file = .mcworld ファイル
+option-world-new-name = 新しいワールドのフォルダ名 (省略時はワールド名)
+world-list-title = 🗺️ ワールド ({ $count } 個):
+world-list-empty = ワールドが見つかりません。
+world-list-entry = • **{ $name }** { $size }
+world-active = ✅ 使用中
+world-switching = ⏳ **{ $name }** に切り替えています...
+world-switched = ✅ **{ $previous }** から **{ $name }** に切り替えて、サーバーを再起動しました。
+world-switched-stopped = ✅ **{ $previous }** から **{ $name }** に切り替えました。次にサーバーを起動したときに読み込まれます。
+world-switch-failed = ❌ ワールドを切り替えられませんでした: { $error }
+world-uploaded = ✅ ワールド **{ $name }** ({ $size }) を追加しました。/world switch で読み込めます。
+world-upload-failed = ❌ ワールドを追加できませんでした: { $error }
+world-failed = ❌ ワールドの操作に失敗しました: { $error }
+
+## Resource usage
+status-resources = 📈 CPU: { $cpu } / メモリ: { $memory }
+cmd-stats-desc = サーバーの状態・稼働時間・CPU とメモリの使用量を表示します
+stats-title = 📊 **サーバーの状況**
+stats-state = 状態: { $state }
+stats-state-stopped = 🔴 停止中
+stats-state-starting = 🟡 起動中
+stats-state-running = 🟢 稼働中
+stats-state-stopping = 🟠 停止処理中
+stats-uptime = 稼働時間: { $uptime }
+stats-players = 接続中のプレイヤー: { $players } 人
+stats-cpu = CPU: { $cpu }
+stats-memory = メモリ: { $memory }
+stats-unavailable = CPU とメモリの使用量は取得できません (サーバーが停止しているか、ボットが Linux 以外で動いています)。
+memory-alert = ⚠️ bedrock_server のメモリ使用量が { $memory } になりました (上限 { $limit })。
+memory-restart-scheduled = 🔄 { $time } に再起動を予約しました。
+memory-restart-pending = 🔄 { $time } にすでに再起動が予約されています。
@@ -0,0 +1,311 @@
+## 슬래시 명령어
+cmd-server-desc = Minecraft 서버 allowlist에 등록합니다
+cmd-restart-desc = Minecraft 서버를 재시작합니다
+
+## /server
+server-modal-title = 서버 등록
+server-modal-label = 게임 ID
+server-modal-placeholder = 게임 ID를 입력하세요
+server-empty-game-id = ❌ 게임 ID를 입력하세요.
+server-added = ✅ `{ $gamertag }` 님을 화이트리스트에 추가했습니다!
+server-command-failed = ❌ 서버에 명령을 보내지 못했습니다.
+
+## /restart
+restart-in-progress = 🔄 서버를 재시작하는 중...
+
+## 상태
+status-title = Minecraft Bedrock 서버 상태
+status-online = **{ status-title }**
+    서버 IP: `{ $ip }`
+    포트: `{ $port }`
+    서버 상태: 🟢 온라인
+    플레이어: { $players }/{ $max }{ $player_list }
+    마지막 업데이트: { $time }
+status-offline = **{ status-title }**
+    서버 IP: `{ $ip }`
+    포트: `{ $port }`
+    서버 상태: 🔴 오프라인
+    마지막 업데이트: { $time }
+status-restart-pending = 🔄 재시작 예정: { $time }
+
+## 관리 콘솔
+console-not-authorized = ❌ 서버 명령을 실행할 권한이 없습니다.
+console-confirm = ⚠️ `{ $command }` 는 위험한 명령입니다. 실행하려면 { $seconds }초 안에 `{ $word }` 를 보내세요.
+console-nothing-to-confirm = ❌ 확인을 기다리는 명령이 없습니다 (만료되었을 수 있습니다).
+console-no-output = (출력 없음)
+console-failed = ❌ 명령을 보내지 못했습니다: { $error }
+console-single-line = ❌ 메시지 하나에 명령 하나씩 보내세요.
+
+## 채팅 브리지
+bridge-death = 💀 { $message }
+
+## 플레이어
+cmd-players-desc = 온라인 플레이어를 표시합니다
+players-title = 👥 온라인 플레이어 ({ $count }명)
+players-empty = 현재 온라인인 플레이어가 없습니다.
+players-entry = • { $name } ({ $duration } 전부터)
+player-joined = 📥 **{ $player }** 님이 게임에 참가했습니다
+player-left = 📤 **{ $player }** 님이 게임에서 나갔습니다
+
+## 플레이 시간
+cmd-playtime-desc = 플레이어의 플레이 시간을 표시합니다
+cmd-leaderboard-desc = 플레이 시간 순위를 표시합니다
+option-player = 플레이어 이름 (게이머태그)
+option-period = 집계 기간
+period-day = 최근 24시간
+period-week = 최근 7일
+period-month = 최근 30일
+period-all = 전체 기간
+playtime-specify-player = ❌ 플레이어 이름을 지정하세요.
+playtime-not-found = ❌ `{ $player }` 의 플레이 기록이 없습니다.
+playtime-title = ⏱️ **{ $player }** 의 플레이 시간
+playtime-line = { $period }: { $duration }
+leaderboard-title = 🏆 플레이 시간 순위 ({ $period })
+leaderboard-entry = { $rank }. { $name } — { $duration }
+leaderboard-empty = 아직 플레이 기록이 없습니다.
+
+## 공통
+not-admin = ❌ 이 명령을 실행할 권한이 없습니다.
+
+## allowlist 정리
+cmd-allowlist-desc = allowlist를 관리합니다
+cmd-allowlist-prune-desc = 오랫동안 접속하지 않은 플레이어를 allowlist에서 삭제합니다
+option-days = 며칠 동안 접속하지 않은 플레이어를 대상으로 할지
+option-dry-run = 삭제하지 않고 대상만 표시합니다
+prune-none = ✅ { $days }일 이상 접속하지 않은 플레이어가 없습니다.
+prune-dry-run-title = 🔍 { $days }일 이상 접속하지 않은 플레이어: { $count }명 (확인만, 삭제하지 않음)
+prune-done-title = 🧹 { $days }일 이상 접속하지 않은 { $count }명을 allowlist에서 삭제했습니다
+prune-entry = • { $name } — 마지막 접속 { $last_seen }
+prune-failed = ❌ allowlist 정리에 실패했습니다: { $error }
+prune-dm = 게이머태그 `{ $gamertag }` 는 { $days }일 이상 서버에 접속하지 않아 allowlist에서 삭제되었습니다. 다시 플레이하려면 /server 로 다시 등록하세요.
+
+## 게스트 패스
+cmd-guest-desc = 기한을 정해 플레이어를 allowlist에 추가합니다
+option-gamertag = 게이머태그
+option-duration = 기간 (예: 2d, 48h, 30m)
+guest-added = ✅ `{ $gamertag }` 님을 { $expires } 까지 allowlist에 추가했습니다.
+guest-invalid-duration = ❌ 기간 `{ $duration }` 을(를) 읽을 수 없습니다. `2d` `48h` `30m` 처럼 지정하세요.
+guest-failed = ❌ 게스트를 추가하지 못했습니다: { $error }
+guest-kick-reason = 게스트 패스가 만료되었습니다
+
+## 재시작 예약
+option-restart-delay = 지정한 분 후에 재시작합니다 (게임 내 카운트다운 안내)
+option-restart-cancel = 예약된 재시작을 취소합니다
+restart-scheduled = ⏰ 서버 재시작을 예약했습니다 ({ $time }).
+restart-delay-too-long = ❌ 지연은 최대 { $max }까지입니다.
+restart-cancelled = ✅ 예약된 재시작을 취소했습니다.
+restart-nothing-to-cancel = ❌ 예약된 재시작이 없습니다.
+restart-warning-minutes = §e{ $minutes }분 후 서버를 재시작합니다
+restart-warning-seconds = §c{ $seconds }초 후 서버를 재시작합니다
+
+## 백업
+cmd-backup-desc = 월드 백업을 관리합니다
+cmd-backup-now-desc = 지금 월드를 백업합니다
+backup-created = 💾 백업 `{ $id }`을(를) 만들었습니다 ({ $size }).
+backup-failed = ❌ 백업에 실패했습니다: { $error }
+cmd-backup-list-desc = 백업 목록을 표시합니다
+cmd-backup-restore-desc = 서버를 멈추고 백업을 복원한 뒤 다시 시작합니다
+option-backup-id = 백업 ID (/backup list 에서 확인)
+backup-list-title = 💾 백업 { $count }개
+backup-list-entry = • `{ $id }` — { $time } ({ $size })
+backup-list-empty = 아직 백업이 없습니다.
+backup-restored = ✅ 백업 `{ $id }` ({ $worlds })을(를) 복원하고 서버를 다시 시작했습니다. 이전 월드는 `<이름>.pre-restore-<시각>` 으로 남겨 두었습니다.
+backup-restored-stopped = ✅ 백업 `{ $id }` ({ $worlds })을(를) 복원했습니다. 서버는 정지된 상태로 두었습니다. 이전 월드는 `<이름>.pre-restore-<시각>` 으로 남겨 두었습니다.
+backup-restore-failed = ❌ 백업 `{ $id }` 복원에 실패했습니다: { $error }
+
+## 라이프사이클 훅
+restart-failed = ❌ 재시작을 중단했습니다: { $error }
+restart-aborted-announce = §c예정된 재시작이 취소되었습니다
+
+## server.properties
+cmd-config-desc = server.properties 를 보거나 변경합니다
+cmd-config-get-desc = 설정을 표시합니다 (키를 생략하면 전체 표시)
+cmd-config-set-desc = 설정을 변경합니다
+option-config-key = 키 (예: difficulty, max-players)
+option-config-value = 새 값
+config-title = ⚙️ server.properties
+config-value = `{ $key }` = `{ $value }`
+config-not-found = ❌ `{ $key }` 은(는) server.properties 에 없습니다.
+config-updated = ✅ `{ $key }`: `{ $previous }` → `{ $value }`
+config-restart-needed = ⚠️ 이 변경을 적용하려면 서버를 재시작해야 합니다. 준비되면 /restart 를 실행하세요.
+config-failed = ❌ { $error }
+config-unknown-key = ❌ `{ $key }` 은(는) 알려진 설정이 아니며 server.properties 에도 없습니다.
+config-invalid-single-line = ❌ 값은 한 줄로 지정하세요.
+config-invalid-bool = ❌ `{ $key }` 에는 true 또는 false 를 지정하세요.
+config-invalid-range = ❌ `{ $key }` 에는 { $min } 부터 { $max } 까지의 정수를 지정하세요.
+config-invalid-choice = ❌ `{ $key }` 에는 다음 중 하나를 지정하세요: { $choices }
+
+## 관리자
+cmd-op-desc = 플레이어의 권한을 설정합니다 (기본값은 관리자)
+cmd-deop-desc = 플레이어의 권한 설정을 삭제합니다 (기본 권한으로 돌아갑니다)
+cmd-ops-desc = permissions.json 의 내용을 표시합니다
+option-linked-user = /server 로 등록한 Discord 사용자 (게이머태그 대신)
+option-permission-level = 권한
+permission-visitor = 방문자
+permission-member = 멤버
+permission-operator = 관리자
+op-specify-player = ❌ 게이머태그 또는 /server 로 등록한 Discord 사용자를 지정하세요.
+op-xuid-unknown = ❌ `{ $gamertag }` 의 XUID를 알 수 없습니다. 먼저 서버에 한 번 접속해야 합니다.
+op-updated = ✅ `{ $gamertag }` 의 권한을 { $level }(으)로 설정했습니다.
+deop-done = ✅ `{ $gamertag }` 의 권한 설정을 삭제했습니다.
+op-next-start = ℹ️ 다음에 서버가 시작될 때 적용됩니다.
+op-failed = ❌ permissions.json 업데이트에 실패했습니다: { $error }
+ops-title = 🛡️ permissions.json ({ $count }건)
+ops-entry = • { $name } (`{ $xuid }`) — { $level }
+ops-empty = permissions.json 에 등록된 항목이 없습니다.
+
+## 관리
+cmd-kick-desc = 플레이어를 서버에서 추방합니다
+cmd-ban-desc = 플레이어를 차단합니다 (추방하고 allowlist에서 삭제하며 /server 등록을 거부)
+cmd-unban-desc = 차단을 해제합니다
+option-reason = 사유
+option-ban-duration = 차단 기간 (예: 7d, 12h. 생략하면 영구)
+kick-done = 👢 `{ $gamertag }` 을(를) 추방했습니다.
+ban-done = 🔨 `{ $gamertag }` 을(를) 차단했습니다 (기한: { $until }).
+ban-permanent = 영구
+ban-already = ❌ `{ $gamertag }` 은(는) 차단되어 있습니다 (기한: { $until }).
+unban-done = ✅ `{ $gamertag }` 의 차단을 해제했습니다.
+unban-not-found = ❌ `{ $gamertag }` 은(는) 차단되어 있지 않습니다.
+moderation-failed = ❌ { $error }
+server-banned = ❌ 차단되어 있어 등록할 수 없습니다 (기한: { $until }).
+
+## 감사 로그
+cmd-audit-desc = 관리 작업의 감사 로그
+cmd-audit-search-desc = 감사 로그를 검색합니다 (최신순)
+option-audit-user = 이 사용자의 작업만
+option-audit-command = 이 명령어만 (예: ban, backup restore)
+option-audit-text = 인수 또는 결과에 포함된 문자열
+option-audit-limit = 표시할 최대 건수
+audit-entry = { $icon } { $actor } `/{ $command }` { $arguments } → { $result }
+audit-actor-bot = (봇)
+audit-search-title = 📝 감사 로그 { $count }건
+audit-search-entry = { $time } { $icon } { $actor } `/{ $command }` { $arguments } → { $result }
+audit-search-empty = 일치하는 기록이 없습니다.
+audit-search-failed = ❌ 감사 로그를 읽지 못했습니다: { $error }
+
+## 속도 제한
+rate-limited = ⏳ 너무 자주 실행했습니다. { $time } 다시 시도하세요.
+
+## 확인
+confirm-prompt = ⚠️ { $command } 을(를) 실행할까요? 되돌릴 수 없습니다. 버튼은 { $expires } 만료됩니다.
+confirm-button = 실행
+cancel-button = 취소
+confirm-running = ⏳ 실행 중…
+confirm-cancelled = 취소했습니다.
+confirm-expired = ⌛ 확인 기한이 지났습니다. 명령어를 다시 실행하세요.
+confirm-not-yours = ❌ 명령어를 실행한 사용자만 이 버튼을 사용할 수 있습니다.
+
+## /start 와 /stop
+cmd-start-desc = 마인크래프트 서버를 시작합니다
+cmd-stop-desc = 마인크래프트 서버를 중지합니다
+start-already-running = 서버가 이미 실행 중입니다.
+start-in-progress = ⏳ 서버를 시작하는 중...
+start-done = 🟢 서버가 시작되었습니다 ({ $duration }).
+start-failed = ❌ 서버를 시작하지 못했습니다: { $error }
+stop-not-running = 서버가 실행 중이 아닙니다.
+stop-in-progress = ⏳ 서버를 중지하는 중...
+stop-done = 🔴 서버가 중지되었습니다.
+stop-failed = ❌ 서버를 중지하지 못했습니다: { $error }
+server-state-starting = 🟡 시작 중... 월드를 불러오고 있습니다.
+server-state-running = 🟢 실행 중. 시작 후 훅을 실행하고 있습니다...
+server-state-stopping = 🟠 중지 중... 월드를 저장하고 있습니다.
+server-state-stopped = 🔴 중지되었습니다.
+
+## 점검
+cmd-maintenance-desc = 점검 모드
+cmd-maintenance-on-desc = 점검을 위해 서버를 닫습니다
+cmd-maintenance-off-desc = 점검을 끝내고 대기 중인 등록을 반영합니다
+option-maintenance-message = 상태 메시지와 게임 안에 표시할 사유
+option-maintenance-eta = 예상 소요 시간 (예: 30m, 2h)
+option-maintenance-mode = 서버를 닫는 방법 (기본: 중지)
+maintenance-mode-stop = 서버 중지
+maintenance-mode-lock = 실행은 유지하고 관리자만 접속
+status-maintenance = **{ status-title }**
+    서버 IP: `{ $ip }`
+    포트: `{ $port }`
+    서버 상태: 🟡 점검 중
+    사유: { $reason }
+    종료 예정: { $eta }
+    마지막 업데이트: { $time }
+maintenance-starting = ⏳ 점검을 시작하는 중...
+maintenance-on = 🟡 점검을 시작했습니다 ({ $mode }). 끝날 때까지 /server 등록은 대기합니다.
+maintenance-already-on = 이미 점검 중입니다.
+maintenance-ending = ⏳ 점검을 끝내는 중...
+maintenance-off = 🟢 점검이 끝났습니다. 대기 중인 등록 { $queued }건 중 { $applied }건을 반영했습니다.
+maintenance-not-active = 점검 중이 아닙니다.
+maintenance-failed = ❌ 점검 상태를 바꾸지 못했습니다: { $error }
+maintenance-refused = ❌ 점검을 위해 서버를 중지했습니다. /maintenance off 로 점검을 끝내세요.
+server-queued = 🟡 서버가 점검 중입니다. **{ $gamertag }** 은(는) 점검이 끝나면 추가되며 (예정: { $eta }) DM으로 알려드립니다.
+
+## 서버 업데이트
+cmd-update-desc = Bedrock Dedicated Server 를 업데이트합니다
+cmd-update-check-desc = 실행 중인 버전과 최신 버전을 비교합니다
+cmd-update-install-desc = 백업 후 새 서버를 설치하고 다시 시작합니다
+option-update-source = 서버 zip 의 URL 또는 경로 (기본: 최신 버전)
+update-versions = 실행 중: `{ $current }` / 최신: `{ $latest }`
+update-available = ⬆️ 업데이트가 있습니다. /update install 로 설치하세요.
+update-up-to-date = ✅ 서버가 최신 버전입니다.
+update-current-unknown = 서버가 중지되어 있어 실행 중인 버전을 알 수 없습니다.
+update-check-failed = ❌ 업데이트를 확인하지 못했습니다: { $error }
+update-in-progress = ⏳ 서버를 업데이트하는 중 (백업, 중지, 설치, 시작)...
+update-done = ✅ { $version } 으로 업데이트했습니다 ({ $files }개 파일 교체). 이전 파일: `{ $backup }`
+update-failed = ❌ 업데이트에 실패했습니다: { $error }
+
+## Add-ons
+cmd-addon-desc = 행동 팩과 리소스 팩을 관리합니다
+cmd-addon-install-desc = .mcpack 또는 .mcaddon을 설치하고 월드에서 활성화합니다
+cmd-addon-list-desc = 월드에서 활성화된 팩 목록을 표시합니다
+cmd-addon-remove-desc = 팩을 비활성화하고 삭제합니다
+option-addon-file = .mcpack 또는 .mcaddon 파일
+option-addon-pack = 팩 이름 또는 UUID
+addon-kind-behavior = 행동
+addon-kind-resource = 리소스
+addon-installed = 📦 팩 { $count }개를 설치했습니다:
+addon-restart-needed = 적용하려면 서버를 재시작하세요.
+addon-install-failed = ❌ 애드온을 설치하지 못했습니다: { $error }
+upload-too-large = ❌ `{ $file }` 은(는) 너무 큽니다 ({ $size }). { $max } 까지의 파일만 받습니다.
+addon-list-title = 📦 월드의 팩 ({ $count }개):
+addon-list-empty = 월드에서 활성화된 팩이 없습니다.
+addon-list-entry = • **{ $name }** { $version } ({ $kind }) `{ $uuid }`
+addon-list-entry-missing = • `{ $uuid }` { $version } ({ $kind }) ⚠️ 팩 폴더가 없습니다
+addon-removed = 🗑️ **{ $pack }**을(를) 삭제했습니다.
+addon-not-found = 월드에서 **{ $pack }**(이)라는 팩이 활성화되어 있지 않습니다.
+addon-failed = ❌ 애드온 작업에 실패했습니다: { $error }
+
+## Worlds
+cmd-world-desc = worlds 폴더의 월드를 관리합니다
+cmd-world-list-desc = 월드 목록을 표시합니다
+cmd-world-switch-desc = level-name을 다른 월드로 바꾸고 서버를 재시작합니다
+cmd-world-upload-desc = .mcworld 파일을 새 월드로 추가합니다
+option-world-name = 월드 폴더 이름
+option-world-file = .mcworld 파일
+option-world-new-name = 새 월드의 폴더 이름 (기본값: 월드 이름)
+world-list-title = 🗺️ 월드 ({ $count }개):
+world-list-empty = 월드를 찾을 수 없습니다.
+world-list-entry = • **{ $name }** { $size }
+world-active = ✅ 사용 중
+world-switching = ⏳ **{ $name }**(으)로 전환하는 중...
+world-switched = ✅ **{ $previous }**에서 **{ $name }**(으)로 전환하고 서버를 재시작했습니다.
+world-switched-stopped = ✅ **{ $previous }**에서 **{ $name }**(으)로 전환했습니다. 다음에 서버를 시작할 때 불러옵니다.
+world-switch-failed = ❌ 월드를 전환하지 못했습니다: { $error }
+world-uploaded = ✅ 월드 **{ $name }** ({ $size })을(를) 추가했습니다. /world switch로 불러올 수 있습니다.
+world-upload-failed = ❌ 월드를 추가하지 못했습니다: { $error }
+world-failed = ❌ 월드 작업에 실패했습니다: { $error }
+
+## Resource usage
+status-resources = 📈 CPU: { $cpu } / 메모리: { $memory }
+cmd-stats-desc = 서버 상태, 가동 시간, CPU와 메모리 사용량을 표시합니다
+stats-title = 📊 **서버 상태**
+stats-state = 상태: { $state }
+stats-state-stopped = 🔴 정지됨
+stats-state-starting = 🟡 시작 중
+stats-state-running = 🟢 실행 중
+stats-state-stopping = 🟠 정지 중
+stats-uptime = 가동 시간: { $uptime }
+stats-players = 접속 중인 플레이어: { $players }명
+stats-cpu = CPU: { $cpu }
+stats-memory = 메모리: { $memory }
+stats-unavailable = CPU와 메모리 사용량을 가져올 수 없습니다 (서버가 정지되어 있거나 봇이 Linux가 아닌 환경에서 실행 중입니다).
+memory-alert = ⚠️ bedrock_server의 메모리 사용량이 { $memory }에 도달했습니다 (상한 { $limit }).
+memory-restart-scheduled = 🔄 { $time }에 재시작을 예약했습니다.
+memory-restart-pending = 🔄 이미 { $time }에 재시작이 예약되어 있습니다.
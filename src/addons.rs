@@ -0,0 +1,400 @@
+use crate::properties::ServerConfig;
+use crate::server_controller::ServerController;
+use crate::store::{load_json, save_json};
+use crate::unzip::UnzipLimit;
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::fs;
+use std::fs::File;
+use std::io::{Cursor, Read, Seek};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use zip::ZipArchive;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackKind {
+    Behavior,
+    Resource,
+}
+
+impl PackKind {
+    pub const ALL: [PackKind; 2] = [PackKind::Behavior, PackKind::Resource];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            PackKind::Behavior => "behavior",
+            PackKind::Resource => "resource",
+        }
+    }
+
+    /// サーバーのフォルダの中でパックを置く場所
+    fn dir_name(self) -> &'static str {
+        match self {
+            PackKind::Behavior => "behavior_packs",
+            PackKind::Resource => "resource_packs",
+        }
+    }
+
+    /// ワールドで有効にするパックの一覧
+    fn world_file(self) -> &'static str {
+        match self {
+            PackKind::Behavior => "world_behavior_packs.json",
+            PackKind::Resource => "world_resource_packs.json",
+        }
+    }
+
+    /// manifest.json の modules[].type から判定する
+    fn from_module(module_type: &str) -> Option<Self> {
+        match module_type {
+            "data" | "script" | "javascript" | "client_data" => Some(PackKind::Behavior),
+            "resources" => Some(PackKind::Resource),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Pack {
+    pub name: String,
+    pub uuid: String,
+    pub version: [u32; 3],
+    pub kind: PackKind,
+    /// インストール先のフォルダ名。ワールドに登録されているがフォルダがない場合は None
+    pub dir: Option<String>,
+}
+
+impl Pack {
+    pub fn version_string(&self) -> String {
+        self.version.map(|part| part.to_string()).join(".")
+    }
+}
+
+#[derive(Deserialize)]
+struct Manifest {
+    header: ManifestHeader,
+    #[serde(default)]
+    modules: Vec<ManifestModule>,
+}
+
+#[derive(Deserialize)]
+struct ManifestHeader {
+    #[serde(default)]
+    name: String,
+    uuid: String,
+    version: Value,
+}
+
+#[derive(Deserialize)]
+struct ManifestModule {
+    #[serde(rename = "type")]
+    kind: String,
+    uuid: String,
+    version: Value,
+}
+
+fn is_uuid(text: &str) -> bool {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN
+        .get_or_init(|| Regex::new(r"^[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}$").unwrap())
+        .is_match(text)
+}
+
+/// `[1, 0, 0]` か、format_version 3 の `"1.0.0"` (後ろの `-beta` などは無視する)
+fn parse_version(value: &Value) -> Option<[u32; 3]> {
+    let parts: Vec<u32> = match value {
+        Value::Array(parts) => parts.iter().map(|part| part.as_u64().and_then(|part| u32::try_from(part).ok())).collect::<Option<_>>()?,
+        Value::String(text) => text
+            .split(['-', '+'])
+            .next()?
+            .split('.')
+            .map(|part| part.parse().ok())
+            .collect::<Option<_>>()?,
+        _ => return None,
+    };
+    parts.try_into().ok()
+}
+
+/// manifest.json を検証し、パックの情報を返す
+fn parse_manifest(bytes: &[u8]) -> Result<(String, String, [u32; 3], PackKind), String> {
+    let manifest: Manifest = serde_json::from_slice(bytes).map_err(|e| format!("invalid manifest.json: {}", e))?;
+    let header = manifest.header;
+    if !is_uuid(&header.uuid) {
+        return Err(format!("invalid header uuid: {}", header.uuid));
+    }
+    let version = parse_version(&header.version).ok_or_else(|| format!("invalid header version: {}", header.version))?;
+    if manifest.modules.is_empty() {
+        return Err("manifest.json has no modules".to_string());
+    }
+    let mut kind = None;
+    for module in &manifest.modules {
+        if !is_uuid(&module.uuid) {
+            return Err(format!("invalid module uuid: {}", module.uuid));
+        }
+        if parse_version(&module.version).is_none() {
+            return Err(format!("invalid module version: {}", module.version));
+        }
+        let Some(module_kind) = PackKind::from_module(&module.kind) else {
+            return Err(format!("unsupported module type: {}", module.kind));
+        };
+        if kind.is_some_and(|kind| kind != module_kind) {
+            return Err("a pack cannot be both a behavior pack and a resource pack".to_string());
+        }
+        kind = Some(module_kind);
+    }
+    let name = if header.name.trim().is_empty() { header.uuid.clone() } else { header.name.trim().to_string() };
+    Ok((name, header.uuid.to_lowercase(), version, kind.unwrap()))
+}
+
+/// 受け付ける .mcpack / .mcaddon の大きさ
+pub const MAX_UPLOAD_SIZE: u64 = 100 << 20;
+/// 展開後のファイル数と合計サイズの上限 (.mcaddon の中の .mcpack も含める)
+const MAX_ENTRIES: usize = 20_000;
+const MAX_UNPACKED_SIZE: u64 = 1 << 30;
+
+/// 一時フォルダに展開したパック1つ分
+struct ExtractedPack {
+    name: String,
+    uuid: String,
+    version: [u32; 3],
+    kind: PackKind,
+    /// manifest.json のあるフォルダ
+    dir: PathBuf,
+}
+
+/// .mcpack (manifest.json を含む zip) か、.mcpack やパックのフォルダをまとめた .mcaddon を
+/// 一時フォルダに展開する。zip ごとに番号のフォルダを作る
+struct PackExtractor {
+    work: PathBuf,
+    next: usize,
+    limit: UnzipLimit,
+    packs: Vec<ExtractedPack>,
+}
+
+impl PackExtractor {
+    fn new(work: PathBuf) -> Self {
+        Self {
+            work,
+            next: 0,
+            limit: UnzipLimit::new(MAX_ENTRIES, MAX_UNPACKED_SIZE),
+            packs: Vec::new(),
+        }
+    }
+
+    fn temp_path(&mut self) -> PathBuf {
+        self.next += 1;
+        self.work.join(self.next.to_string())
+    }
+
+    fn extract(&mut self, reader: impl Read + Seek) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut archive = ZipArchive::new(reader)?;
+        let dir = self.temp_path();
+        let mut roots = Vec::new();
+        for i in 0..archive.len() {
+            let mut file = archive.by_index(i)?;
+            if file.is_dir() {
+                continue;
+            }
+            let name = file.enclosed_name().ok_or_else(|| format!("unsafe path in add-on: {}", file.name()))?;
+            let extension = name.extension().map(|ext| ext.to_string_lossy().to_lowercase());
+            if extension.as_deref() == Some("mcpack") {
+                let nested = self.temp_path();
+                self.limit.extract(&mut file, &nested)?;
+                self.extract(File::open(&nested)?)?;
+                let _ = fs::remove_file(&nested);
+                continue;
+            }
+            if name.file_name().is_some_and(|file_name| file_name == "manifest.json") {
+                roots.push(name.parent().unwrap_or(Path::new("")).to_path_buf());
+            }
+            self.limit.extract(&mut file, &dir.join(&name))?;
+        }
+        // パックの中にある manifest.json (サブパックなど) は別のパックとして扱わない
+        roots.sort_by_key(|root| root.components().count());
+        let mut top_roots: Vec<PathBuf> = Vec::new();
+        for root in roots {
+            if !top_roots.iter().any(|top| root.starts_with(top)) {
+                top_roots.push(root);
+            }
+        }
+        for root in top_roots {
+            let manifest = fs::read(dir.join(&root).join("manifest.json"))?;
+            let (name, uuid, version, kind) =
+                parse_manifest(&manifest).map_err(|e| format!("{}: {}", root.join("manifest.json").display(), e))?;
+            self.packs.push(ExtractedPack { name, uuid, version, kind, dir: dir.join(root) });
+        }
+        Ok(())
+    }
+}
+
+/// パック名からフォルダ名を作る
+fn dir_name_for(name: &str, uuid: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .take(48)
+        .collect();
+    let name = name.trim_matches('_');
+    if name.is_empty() { uuid.to_string() } else { name.to_string() }
+}
+
+/// ビヘイビアーパック・リソースパックのインストールとワールドへの登録
+pub struct Addons {
+    server_dir: PathBuf,
+    worlds_dir: PathBuf,
+    config: Arc<ServerConfig>,
+    // インストールと削除でパックのフォルダやワールドのパック一覧の更新が重ならないようにする
+    lock: Mutex<()>,
+}
+
+impl Addons {
+    /// level-name のワールドに登録する
+    pub fn from_env(server_controller: &ServerController, config: Arc<ServerConfig>) -> Self {
+        Self {
+            server_dir: server_controller.server_dir(),
+            worlds_dir: server_controller.worlds_dir(),
+            config,
+            lock: Mutex::new(()),
+        }
+    }
+
+    fn world_dir(&self) -> Result<PathBuf, Box<dyn std::error::Error + Send + Sync>> {
+        let world_dir = self.worlds_dir.join(self.config.level_name()?);
+        if !world_dir.is_dir() {
+            return Err(format!("world folder not found: {}", world_dir.display()).into());
+        }
+        Ok(world_dir)
+    }
+
+    /// インストール済みのパック (manifest.json を読めるもの) を (フォルダ名, パック) で返す
+    fn installed(&self, kind: PackKind) -> Vec<(String, Pack)> {
+        let Ok(entries) = fs::read_dir(self.server_dir.join(kind.dir_name())) else {
+            return Vec::new();
+        };
+        entries
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                let dir = path.file_name()?.to_str()?.to_string();
+                let (name, uuid, version, kind) = parse_manifest(&fs::read(path.join("manifest.json")).ok()?).ok()?;
+                Some((dir.clone(), Pack { name, uuid, version, kind, dir: Some(dir) }))
+            })
+            .filter(|(_, pack)| pack.kind == kind)
+            .collect()
+    }
+
+    /// .mcpack / .mcaddon を展開してワールドに登録し、インストールしたパックを返す。
+    /// 同じ UUID のパックがすでにあれば置き換える
+    pub fn install(&self, file_name: &str, bytes: &[u8]) -> Result<Vec<Pack>, Box<dyn std::error::Error + Send + Sync>> {
+        let extension = Path::new(file_name).extension().map(|ext| ext.to_string_lossy().to_lowercase());
+        if !matches!(extension.as_deref(), Some("mcpack" | "mcaddon" | "zip")) {
+            return Err(format!("{} is not a .mcpack or .mcaddon file", file_name).into());
+        }
+        let _lock = self.lock.lock().unwrap();
+        // 展開先はパックのフォルダへ rename できるよう、サーバーのフォルダの中に作る
+        let work = self.server_dir.join(".addon-upload");
+        let _ = fs::remove_dir_all(&work);
+        let result = self.install_extracted(bytes, &work);
+        let _ = fs::remove_dir_all(&work);
+        result
+    }
+
+    fn install_extracted(&self, bytes: &[u8], work: &Path) -> Result<Vec<Pack>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut extractor = PackExtractor::new(work.to_path_buf());
+        extractor.extract(Cursor::new(bytes))?;
+        if extractor.packs.is_empty() {
+            return Err("no manifest.json found in the add-on".into());
+        }
+        let world_dir = self.world_dir()?;
+        let mut installed = Vec::new();
+        for archive in extractor.packs {
+            let packs_dir = self.server_dir.join(archive.kind.dir_name());
+            let existing = self.installed(archive.kind).into_iter().find(|(_, pack)| pack.uuid == archive.uuid);
+            let dir = match existing {
+                Some((dir, _)) => dir,
+                None => {
+                    let mut dir = dir_name_for(&archive.name, &archive.uuid);
+                    if packs_dir.join(&dir).exists() {
+                        dir = format!("{}_{}", dir, &archive.uuid[..8]);
+                    }
+                    dir
+                }
+            };
+            let target = packs_dir.join(&dir);
+            fs::create_dir_all(&packs_dir)?;
+            if target.exists() {
+                fs::remove_dir_all(&target)?;
+            }
+            fs::rename(&archive.dir, &target)?;
+            register(&world_dir.join(archive.kind.world_file()), &archive.uuid, Some(archive.version))?;
+            println!("Installed {} pack {} ({}) to {:?}", archive.kind.name(), archive.name, archive.uuid, target);
+            installed.push(Pack {
+                name: archive.name,
+                uuid: archive.uuid,
+                version: archive.version,
+                kind: archive.kind,
+                dir: Some(dir),
+            });
+        }
+        Ok(installed)
+    }
+
+    /// ワールドに登録されているパック
+    pub fn list(&self) -> Result<Vec<Pack>, Box<dyn std::error::Error + Send + Sync>> {
+        let world_dir = self.world_dir()?;
+        let mut packs = Vec::new();
+        for kind in PackKind::ALL {
+            let installed = self.installed(kind);
+            let entries: Vec<Value> = load_json(&world_dir.join(kind.world_file()))?;
+            for entry in entries {
+                let Some(uuid) = entry.get("pack_id").and_then(Value::as_str).map(str::to_lowercase) else { continue };
+                let pack = match installed.iter().find(|(_, pack)| pack.uuid == uuid) {
+                    Some((_, pack)) => pack.clone(),
+                    None => Pack {
+                        name: uuid.clone(),
+                        version: entry.get("version").and_then(parse_version).unwrap_or_default(),
+                        uuid,
+                        kind,
+                        dir: None,
+                    },
+                };
+                packs.push(pack);
+            }
+        }
+        Ok(packs)
+    }
+
+    /// UUID か名前で指定したパックをワールドから外し、フォルダを削除する。なければ None
+    pub fn remove(&self, query: &str) -> Result<Option<Pack>, Box<dyn std::error::Error + Send + Sync>> {
+        let query = query.trim();
+        let packs = self.list()?;
+        let Some(pack) = packs
+            .into_iter()
+            .find(|pack| pack.uuid.eq_ignore_ascii_case(query) || pack.name.eq_ignore_ascii_case(query))
+        else {
+            return Ok(None);
+        };
+        let _lock = self.lock.lock().unwrap();
+        let world_dir = self.world_dir()?;
+        register(&world_dir.join(pack.kind.world_file()), &pack.uuid, None)?;
+        if let Some(dir) = &pack.dir {
+            fs::remove_dir_all(self.server_dir.join(pack.kind.dir_name()).join(dir))?;
+        }
+        println!("Removed {} pack {} ({})", pack.kind.name(), pack.name, pack.uuid);
+        Ok(Some(pack))
+    }
+}
+
+/// ワールドのパック一覧から `uuid` を外し、`version` があれば登録し直す。ほかの項目はそのまま残す
+fn register(path: &Path, uuid: &str, version: Option<[u32; 3]>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let mut entries: Vec<Value> = load_json(path)?;
+    entries.retain(|entry| {
+        entry
+            .get("pack_id")
+            .and_then(Value::as_str)
+            .is_none_or(|pack_id| !pack_id.eq_ignore_ascii_case(uuid))
+    });
+    if let Some(version) = version {
+        entries.push(json!({ "pack_id": uuid, "version": version }));
+    }
+    save_json(path, &entries)
+}
@@ -0,0 +1,150 @@
+use crate::audit::{AuditEntry, AuditLog};
+use crate::auth::Admins;
+use crate::console::sanitize_line;
+use crate::i18n::{tr, Language};
+use crate::server_controller::ServerController;
+use serenity::all::{ChannelId, Context, Message, UserId};
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(30);
+const CONFIRM_WORD: &str = "confirm";
+/// コマンド送信後、この時間出力がなければ応答が終わったとみなす
+const RESPONSE_QUIET_PERIOD: Duration = Duration::from_millis(500);
+const MAX_REPLY_LEN: usize = 1900;
+
+/// 管理用チャンネルへの投稿をサーバーコマンドとして転送する
+pub struct AdminConsole {
+    channel_id: ChannelId,
+    admins: Admins,
+    confirm_commands: Vec<String>,
+    pending: Mutex<HashMap<UserId, (String, Instant)>>,
+    server_controller: Arc<ServerController>,
+    audit: Arc<AuditLog>,
+}
+
+impl AdminConsole {
+    /// ADMIN_CONSOLE_CHANNEL_ID が未設定なら None。
+    /// CONSOLE_CONFIRM_COMMANDS (カンマ区切り、既定は stop) に一致するコマンドは確認が必要
+    pub fn from_env(
+        admins: Admins,
+        server_controller: Arc<ServerController>,
+        audit: Arc<AuditLog>,
+    ) -> Result<Option<Self>, Box<dyn std::error::Error + Send + Sync>> {
+        let Ok(channel_id) = env::var("ADMIN_CONSOLE_CHANNEL_ID") else {
+            return Ok(None);
+        };
+        let channel_id = channel_id
+            .parse::<u64>()
+            .map_err(|_| "ADMIN_CONSOLE_CHANNEL_ID must be a valid u64")?;
+        let confirm_commands = env::var("CONSOLE_CONFIRM_COMMANDS")
+            .unwrap_or_else(|_| "stop".to_string())
+            .split(',')
+            .map(|cmd| cmd.trim().to_lowercase())
+            .filter(|cmd| !cmd.is_empty())
+            .collect();
+        Ok(Some(Self {
+            channel_id: ChannelId::new(channel_id),
+            admins,
+            confirm_commands,
+            pending: Mutex::new(HashMap::new()),
+            server_controller,
+            audit,
+        }))
+    }
+
+    pub fn channel_id(&self) -> ChannelId {
+        self.channel_id
+    }
+
+    pub async fn handle_message(&self, ctx: &Context, msg: &Message) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let lang = Language::from_env();
+        let command = msg.content.trim();
+        if command.is_empty() {
+            return Ok(());
+        }
+        let roles = msg.member.as_ref().map(|member| member.roles.as_slice()).unwrap_or_default();
+        if !self.admins.contains(msg.author.id, roles) {
+            self.audit.record(AuditEntry::new(
+                Some(msg.author.id),
+                "console",
+                BTreeMap::from([("command".to_string(), command.to_string())]),
+                Err("permission denied".to_string()),
+            ));
+            msg.reply(&ctx.http, tr!(lang, "console-not-authorized")).await?;
+            return Ok(());
+        }
+
+        // 改行を含むと複数のコマンドとして送られ、確認を回避できてしまう
+        if command.contains(['\r', '\n']) {
+            msg.reply(&ctx.http, tr!(lang, "console-single-line")).await?;
+            return Ok(());
+        }
+
+        let command = if command.eq_ignore_ascii_case(CONFIRM_WORD) {
+            let pending = self.pending.lock().unwrap().remove(&msg.author.id);
+            match pending {
+                Some((command, requested)) if requested.elapsed() < CONFIRM_TIMEOUT => command,
+                _ => {
+                    msg.reply(&ctx.http, tr!(lang, "console-nothing-to-confirm")).await?;
+                    return Ok(());
+                }
+            }
+        } else if self.requires_confirmation(command) {
+            self.pending
+                .lock()
+                .unwrap()
+                .insert(msg.author.id, (command.to_string(), Instant::now()));
+            let reply = tr!(
+                lang,
+                "console-confirm",
+                command = command,
+                word = CONFIRM_WORD,
+                seconds = CONFIRM_TIMEOUT.as_secs(),
+            );
+            msg.reply(&ctx.http, reply).await?;
+            return Ok(());
+        } else {
+            command.to_string()
+        };
+
+        println!("{} ran console command: {}", msg.author.name, command);
+        let result = self.server_controller.send_command_capture(&command, RESPONSE_QUIET_PERIOD).await;
+        self.audit.record(AuditEntry::new(
+            Some(msg.author.id),
+            "console",
+            BTreeMap::from([("command".to_string(), command.clone())]),
+            result.as_ref().map(|lines| format!("{} lines of output", lines.len())).map_err(|e| e.to_string()),
+        ));
+        let reply = match result {
+            Ok(lines) if lines.is_empty() => tr!(lang, "console-no-output"),
+            Ok(lines) => format_output(&lines),
+            Err(e) => tr!(lang, "console-failed", error = e.to_string()),
+        };
+        msg.reply(&ctx.http, reply).await?;
+        Ok(())
+    }
+
+    fn requires_confirmation(&self, command: &str) -> bool {
+        let command = command.trim_start_matches('/').to_lowercase();
+        self.confirm_commands.iter().any(|denied| {
+            command == *denied || command.starts_with(&format!("{} ", denied))
+        })
+    }
+}
+
+fn format_output(lines: &[String]) -> String {
+    let mut body = String::new();
+    for line in lines {
+        let line = sanitize_line(line);
+        if body.len() + line.len() + 1 > MAX_REPLY_LEN {
+            body.push_str("...\n");
+            break;
+        }
+        body.push_str(&line);
+        body.push('\n');
+    }
+    format!("```\n{}```", body)
+}
@@ -0,0 +1,49 @@
+use crate::server_controller::ServerController;
+use crate::store::{load_json, save_json};
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AllowlistEntry {
+    #[serde(rename = "ignoresPlayerLimit", default)]
+    pub ignores_player_limit: bool,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub xuid: Option<String>,
+}
+
+/// サーバーの allowlist.json
+pub struct Allowlist {
+    path: PathBuf,
+}
+
+impl Allowlist {
+    /// ALLOWLIST_PATH (既定はサーバーのフォルダの allowlist.json)
+    pub fn from_env(server_controller: &ServerController) -> Self {
+        let path = match env::var("ALLOWLIST_PATH") {
+            Ok(path) => PathBuf::from(path),
+            Err(_) => server_controller.server_dir().join("allowlist.json"),
+        };
+        Self { path }
+    }
+
+    pub fn entries(&self) -> Result<Vec<AllowlistEntry>, Box<dyn std::error::Error + Send + Sync>> {
+        load_json(&self.path)
+    }
+
+    /// ファイルから削除し、サーバーが動いていれば再読み込みさせる
+    pub fn remove(
+        &self,
+        names: &[String],
+        server_controller: &ServerController,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut entries = self.entries()?;
+        entries.retain(|entry| !names.iter().any(|name| name.eq_ignore_ascii_case(&entry.name)));
+        save_json(&self.path, &entries)?;
+        if server_controller.is_running() {
+            server_controller.send_command("allowlist reload")?;
+        }
+        Ok(())
+    }
+}
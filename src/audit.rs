@@ -0,0 +1,173 @@
+use crate::i18n::{tr, Language};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serenity::all::{ChannelId, CreateAllowedMentions, CreateMessage, Http, UserId};
+use std::collections::BTreeMap;
+use std::env;
+use std::fs::OpenOptions;
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// 監査ログの1件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub timestamp: DateTime<Utc>,
+    /// 操作したDiscordユーザー。None ならボット自身 (スケジュールによる実行など)
+    pub actor: Option<UserId>,
+    pub command: String,
+    #[serde(default)]
+    pub arguments: BTreeMap<String, String>,
+    pub success: bool,
+    pub result: String,
+}
+
+impl AuditEntry {
+    pub fn new(actor: Option<UserId>, command: &str, arguments: BTreeMap<String, String>, result: Result<String, String>) -> Self {
+        let (success, result) = match result {
+            Ok(result) => (true, result),
+            Err(error) => (false, error),
+        };
+        Self {
+            timestamp: Utc::now(),
+            actor,
+            command: command.to_string(),
+            arguments,
+            success,
+            result,
+        }
+    }
+
+    pub fn format_arguments(&self) -> String {
+        self.arguments
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// `/audit search` の条件。None の項目は絞り込まない
+#[derive(Debug, Clone, Default)]
+pub struct AuditQuery {
+    pub actor: Option<UserId>,
+    pub command: Option<String>,
+    /// 引数と結果に含まれる文字列 (大文字小文字は区別しない)
+    pub text: Option<String>,
+}
+
+impl AuditQuery {
+    fn matches(&self, entry: &AuditEntry) -> bool {
+        if self.actor.is_some() && entry.actor != self.actor {
+            return false;
+        }
+        if let Some(command) = &self.command {
+            if !entry.command.to_lowercase().starts_with(&command.to_lowercase()) {
+                return false;
+            }
+        }
+        if let Some(text) = &self.text {
+            let text = text.to_lowercase();
+            let found = entry.result.to_lowercase().contains(&text)
+                || entry.arguments.values().any(|value| value.to_lowercase().contains(&text));
+            if !found {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// 権限の必要な操作を追記専用の JSONL ファイルに記録し、Discordのチャンネルにも流す
+pub struct AuditLog {
+    path: PathBuf,
+    channel_id: Option<ChannelId>,
+    http: OnceLock<Arc<Http>>,
+    file: Mutex<()>,
+}
+
+impl AuditLog {
+    /// AUDIT_LOG_PATH (既定は audit.jsonl) に記録し、AUDIT_CHANNEL_ID が指定されていればそこにも投稿する
+    pub fn from_env() -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let channel_id = match env::var("AUDIT_CHANNEL_ID") {
+            Ok(id) => Some(ChannelId::new(id.parse::<u64>().map_err(|_| "AUDIT_CHANNEL_ID must be a valid u64")?)),
+            Err(_) => None,
+        };
+        Ok(Self {
+            path: PathBuf::from(env::var("AUDIT_LOG_PATH").unwrap_or_else(|_| "audit.jsonl".to_string())),
+            channel_id,
+            http: OnceLock::new(),
+            file: Mutex::new(()),
+        })
+    }
+
+    /// これ以降の記録をチャンネルにも投稿する
+    pub fn start(&self, http: Arc<Http>) {
+        let _ = self.http.set(http);
+    }
+
+    pub fn record(&self, entry: AuditEntry) {
+        println!(
+            "[audit] {} {} {} -> {}",
+            entry.actor.map_or_else(|| "bot".to_string(), |actor| actor.to_string()),
+            entry.command,
+            entry.format_arguments(),
+            entry.result,
+        );
+        if let Err(e) = self.append(&entry) {
+            eprintln!("Failed to write audit log: {}", e);
+        }
+        let (Some(channel_id), Some(http)) = (self.channel_id, self.http.get()) else {
+            return;
+        };
+        let http = Arc::clone(http);
+        tokio::spawn(async move {
+            let lang = Language::from_env();
+            let content = tr!(
+                lang,
+                "audit-entry",
+                icon = if entry.success { "✅" } else { "❌" },
+                actor = entry.actor.map_or_else(|| tr!(lang, "audit-actor-bot"), |actor| format!("<@{}>", actor)),
+                command = entry.command.as_str(),
+                arguments = entry.format_arguments(),
+                result = entry.result.as_str(),
+            );
+            let builder = CreateMessage::new()
+                .content(content)
+                .allowed_mentions(CreateAllowedMentions::new());
+            if let Err(e) = channel_id.send_message(&http, builder).await {
+                eprintln!("Failed to post to the audit channel: {}", e);
+            }
+        });
+    }
+
+    fn append(&self, entry: &AuditEntry) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let _lock = self.file.lock().unwrap();
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        let mut line = serde_json::to_string(entry)?;
+        line.push('\n');
+        file.write_all(line.as_bytes())?;
+        Ok(())
+    }
+
+    /// 条件に合う記録を新しい順に最大 `limit` 件
+    pub fn search(&self, query: &AuditQuery, limit: usize) -> Result<Vec<AuditEntry>, Box<dyn std::error::Error + Send + Sync>> {
+        let file = match std::fs::File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut entries = Vec::new();
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            // 書き込み途中で落ちた行などは読み飛ばす
+            let Ok(entry) = serde_json::from_str::<AuditEntry>(&line) else { continue };
+            if query.matches(&entry) {
+                entries.push(entry);
+            }
+        }
+        entries.reverse();
+        entries.truncate(limit);
+        Ok(entries)
+    }
+}
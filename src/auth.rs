@@ -0,0 +1,34 @@
+use serenity::all::{RoleId, UserId};
+use std::env;
+
+/// サーバーを操作できる管理者 (ADMIN_USER_IDS / ADMIN_ROLE_IDS にカンマ区切りで指定)
+#[derive(Debug, Clone, Default)]
+pub struct Admins {
+    user_ids: Vec<UserId>,
+    role_ids: Vec<RoleId>,
+}
+
+impl Admins {
+    pub fn from_env() -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Self {
+            user_ids: parse_ids("ADMIN_USER_IDS")?.into_iter().map(UserId::new).collect(),
+            role_ids: parse_ids("ADMIN_ROLE_IDS")?.into_iter().map(RoleId::new).collect(),
+        })
+    }
+
+    pub fn contains(&self, user_id: UserId, roles: &[RoleId]) -> bool {
+        self.user_ids.contains(&user_id) || roles.iter().any(|role| self.role_ids.contains(role))
+    }
+}
+
+fn parse_ids(var: &str) -> Result<Vec<u64>, Box<dyn std::error::Error + Send + Sync>> {
+    let Ok(value) = env::var(var) else {
+        return Ok(Vec::new());
+    };
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(|id| id.parse::<u64>().map_err(|_| format!("{} contains an invalid ID: {}", var, id).into()))
+        .collect()
+}
@@ -0,0 +1,480 @@
+use crate::log_parser::strip_prefix;
+use crate::schedule::{next_run, schedule_from_env, sleep_until};
+use crate::server_controller::ServerController;
+use chrono::{DateTime, Datelike, Duration as ChronoDuration, Local, NaiveDateTime, TimeZone, Utc};
+use cron::Schedule;
+use regex::Regex;
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// `save query` の応答を待つ時間
+const QUERY_QUIET: Duration = Duration::from_millis(500);
+/// 保存の完了を確認する回数と間隔
+const QUERY_ATTEMPTS: u32 = 30;
+const QUERY_INTERVAL: Duration = Duration::from_secs(1);
+const ID_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+/// 作成したバックアップ
+#[derive(Debug, Clone)]
+pub struct Backup {
+    pub id: String,
+    pub path: PathBuf,
+    pub size: u64,
+    pub created_at: DateTime<Local>,
+}
+
+/// 展開済みで、入れ替えを待っている復元。使い終わったら一時フォルダを削除する
+pub struct PreparedRestore {
+    id: String,
+    staging: PathBuf,
+    worlds: Vec<String>,
+}
+
+impl Drop for PreparedRestore {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.staging);
+    }
+}
+
+/// 残すバックアップの数。どれも未設定ならすべて残す
+#[derive(Debug, Clone, Copy, Default)]
+struct Retention {
+    /// 新しいものから N 個
+    last: Option<u32>,
+    /// 直近 D 日間は1日1個
+    daily: Option<u32>,
+    /// 直近 W 週間は1週間に1個
+    weekly: Option<u32>,
+}
+
+impl Retention {
+    fn from_env() -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let read = |var: &str| match env::var(var) {
+            Ok(value) => value
+                .parse::<u32>()
+                .map(Some)
+                .map_err(|_| format!("{} must be a valid u32", var)),
+            Err(_) => Ok(None),
+        };
+        Ok(Self {
+            last: read("BACKUP_KEEP_LAST")?,
+            daily: read("BACKUP_KEEP_DAILY")?,
+            weekly: read("BACKUP_KEEP_WEEKLY")?,
+        })
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.last.is_some() || self.daily.is_some() || self.weekly.is_some()
+    }
+
+    /// 新しい順に並んだバックアップのうち、残すものの ID。作ったばかりの最新のものは必ず残す
+    fn keep(&self, backups: &[Backup], now: DateTime<Local>) -> HashSet<String> {
+        let mut keep: HashSet<String> = backups
+            .iter()
+            .take(self.last.unwrap_or(0).max(1) as usize)
+            .map(|backup| backup.id.clone())
+            .collect();
+        if let Some(days) = self.daily {
+            // 表せないほど長い期間なら、すべてを対象にする
+            let cutoff = ChronoDuration::try_days(days.into()).and_then(|days| now.checked_sub_signed(days));
+            let mut seen = HashSet::new();
+            for backup in backups.iter().filter(|backup| cutoff.is_none_or(|cutoff| backup.created_at > cutoff)) {
+                if seen.insert(backup.created_at.date_naive()) {
+                    keep.insert(backup.id.clone());
+                }
+            }
+        }
+        if let Some(weeks) = self.weekly {
+            let cutoff = ChronoDuration::try_weeks(weeks.into()).and_then(|weeks| now.checked_sub_signed(weeks));
+            let mut seen = HashSet::new();
+            for backup in backups.iter().filter(|backup| cutoff.is_none_or(|cutoff| backup.created_at > cutoff)) {
+                if seen.insert(backup.created_at.iso_week()) {
+                    keep.insert(backup.id.clone());
+                }
+            }
+        }
+        keep
+    }
+}
+
+/// `save hold` / `save query` / `save resume` を使ったワールドのバックアップ
+pub struct BackupManager {
+    server_controller: Arc<ServerController>,
+    worlds_dir: PathBuf,
+    backup_dir: PathBuf,
+    schedule: Option<Schedule>,
+    retention: Retention,
+    // 同時に2つのバックアップを取らない
+    running: tokio::sync::Mutex<()>,
+}
+
+impl BackupManager {
+    /// BACKUP_PATH (既定は ./backups) に保存する。
+    /// BACKUP_SCHEDULE に cron 形式で定期バックアップの時刻を指定できる。
+    /// BACKUP_KEEP_LAST / BACKUP_KEEP_DAILY / BACKUP_KEEP_WEEKLY で古いバックアップを削除する
+    pub fn from_env(server_controller: Arc<ServerController>) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let worlds_dir = server_controller.worlds_dir();
+        Ok(Self {
+            server_controller,
+            worlds_dir,
+            backup_dir: PathBuf::from(env::var("BACKUP_PATH").unwrap_or_else(|_| "./backups".to_string())),
+            schedule: schedule_from_env("BACKUP_SCHEDULE")?,
+            retention: Retention::from_env()?,
+            running: tokio::sync::Mutex::new(()),
+        })
+    }
+
+    /// バックアップを作成する。書き込みを止めている間にサーバーが報告したファイルだけを、報告された長さでコピーする
+    pub async fn create(&self) -> Result<Backup, Box<dyn std::error::Error + Send + Sync>> {
+        let _running = self.running.lock().await;
+        if !self.server_controller.is_running() {
+            return Err("server is not running".into());
+        }
+        self.server_controller.send_command("save hold")?;
+        let result = self.copy_held_files().await;
+        // コピーに失敗しても書き込みは必ず再開させる
+        if let Err(e) = self.server_controller.send_command("save resume") {
+            eprintln!("Failed to resume saving: {}", e);
+        }
+        let backup = result?;
+        println!("Backup created: {:?} ({} bytes)", backup.path, backup.size);
+        if let Err(e) = self.apply_retention() {
+            eprintln!("Failed to delete old backups: {}", e);
+        }
+        Ok(backup)
+    }
+
+    pub fn backup_dir(&self) -> &Path {
+        &self.backup_dir
+    }
+
+    /// バックアップの一覧 (新しい順)
+    pub fn list(&self) -> Result<Vec<Backup>, Box<dyn std::error::Error + Send + Sync>> {
+        let entries = match fs::read_dir(&self.backup_dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut backups = Vec::new();
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != "zip") {
+                continue;
+            }
+            let Some(id) = path.file_stem().and_then(|stem| stem.to_str()).map(str::to_string) else {
+                continue;
+            };
+            let Some(created_at) = parse_id(&id) else { continue };
+            let size = fs::metadata(&path)?.len();
+            backups.push(Backup { id, path, size, created_at });
+        }
+        backups.sort_by_key(|backup| Reverse(backup.created_at));
+        Ok(backups)
+    }
+
+    fn apply_retention(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if !self.retention.is_enabled() {
+            return Ok(());
+        }
+        let backups = self.list()?;
+        let keep = self.retention.keep(&backups, Local::now());
+        for backup in backups.iter().filter(|backup| !keep.contains(&backup.id)) {
+            fs::remove_file(&backup.path)?;
+            println!("Deleted old backup {}", backup.id);
+        }
+        Ok(())
+    }
+
+    /// バックアップを worlds の中の一時フォルダに展開する。
+    /// サーバーを止める前に展開しておき、止めている時間を短くする
+    pub async fn prepare_restore(&self, id: &str) -> Result<PreparedRestore, Box<dyn std::error::Error + Send + Sync>> {
+        let _running = self.running.lock().await;
+        let backup = self
+            .list()?
+            .into_iter()
+            .find(|backup| backup.id == id)
+            .ok_or_else(|| format!("backup {} not found", id))?;
+        let staging = self.worlds_dir.join(format!(".restore-{}", backup.id));
+        let zip_path = backup.path.clone();
+        let extract_to = staging.clone();
+        let worlds = tokio::task::spawn_blocking(move || extract_zip(&zip_path, &extract_to)).await?;
+        // 展開に失敗したら作りかけの一時フォルダは drop で消える
+        let mut restore = PreparedRestore { id: backup.id, staging, worlds: Vec::new() };
+        restore.worlds = worlds?;
+        Ok(restore)
+    }
+
+    /// 展開したワールドに入れ替える (サーバーは止めておく)。
+    /// 入れ替える前のワールドは `<ワールド名>.pre-restore-<日時>` として残す
+    pub async fn apply_restore(&self, restore: PreparedRestore) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>> {
+        let _running = self.running.lock().await;
+        let suffix = format!("pre-restore-{}", Local::now().format(ID_FORMAT));
+        swap_worlds(&self.worlds_dir, &restore.staging, &restore.worlds, &suffix)?;
+        println!("Restored backup {} ({})", restore.id, restore.worlds.join(", "));
+        Ok(restore.worlds.clone())
+    }
+
+    async fn copy_held_files(&self) -> Result<Backup, Box<dyn std::error::Error + Send + Sync>> {
+        let files = self.wait_for_files().await?;
+        let created_at = Local::now();
+        let id = created_at.format(ID_FORMAT).to_string();
+        let worlds_dir = self.worlds_dir.clone();
+        let path = self.backup_dir.join(format!("{}.zip", id));
+        let zip_path = path.clone();
+        let size = tokio::task::spawn_blocking(move || write_zip(&worlds_dir, &files, &zip_path)).await??;
+        Ok(Backup { id, path, size, created_at })
+    }
+
+    /// 保存が終わるまで `save query` を繰り返し、(ファイル, 長さ) の一覧を返す
+    async fn wait_for_files(&self) -> Result<Vec<(String, u64)>, Box<dyn std::error::Error + Send + Sync>> {
+        for _ in 0..QUERY_ATTEMPTS {
+            let lines = self.server_controller.send_command_capture("save query", QUERY_QUIET).await?;
+            if let Some(files) = lines.iter().find_map(|line| parse_file_list(strip_prefix(line))) {
+                return Ok(files);
+            }
+            tokio::time::sleep(QUERY_INTERVAL).await;
+        }
+        Err("timed out waiting for the world to be saved".into())
+    }
+
+    /// BACKUP_SCHEDULE の時刻にバックアップを作成する
+    pub fn start_schedule(self: &Arc<Self>) {
+        let Some(schedule) = self.schedule.clone() else { return };
+        let manager = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut after = Utc::now();
+            while let Some(next) = next_run(&schedule, after) {
+                sleep_until(next).await;
+                if let Err(e) = manager.create().await {
+                    eprintln!("Scheduled backup failed: {}", e);
+                }
+                after = next;
+            }
+        });
+    }
+}
+
+fn parse_id(id: &str) -> Option<DateTime<Local>> {
+    let naive = NaiveDateTime::parse_from_str(id, ID_FORMAT).ok()?;
+    Local.from_local_datetime(&naive).earliest()
+}
+
+/// `Bedrock level/db/000005.ldb:1234, Bedrock level/level.dat:2048, ...` の形式の行を読む。
+/// ワールド名に ", " が含まれることがあるので、各項目の終わりは `:<長さ>` で判断する
+fn parse_file_list(line: &str) -> Option<Vec<(String, u64)>> {
+    static ENTRY: OnceLock<Regex> = OnceLock::new();
+    let entry = ENTRY.get_or_init(|| Regex::new(r"(.+?):(\d+)(?:, |$)").unwrap());
+    let line = line.trim_end();
+    let mut files = Vec::new();
+    let mut end = 0;
+    for captures in entry.captures_iter(line) {
+        let whole = captures.get(0)?;
+        // 項目の間に読めない部分があれば、ファイルの一覧ではない
+        if whole.start() != end {
+            return None;
+        }
+        end = whole.end();
+        let path = &captures[1];
+        // ワールドのフォルダからの相対パスなので必ず区切り文字を含む
+        if !path.contains(['/', '\\']) {
+            return None;
+        }
+        files.push((path.to_string(), captures[2].parse().ok()?));
+    }
+    (end == line.len() && !files.is_empty()).then_some(files)
+}
+
+/// 各ファイルの先頭 `length` バイトを zip にまとめる。途中で失敗したら作りかけのファイルは残さない
+fn write_zip(worlds_dir: &Path, files: &[(String, u64)], path: &Path) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+    fs::create_dir_all(path.parent().unwrap_or(Path::new(".")))?;
+    let partial = path.with_extension("zip.partial");
+    let result = (|| -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut zip = ZipWriter::new(File::create(&partial)?);
+        let options = SimpleFileOptions::default()
+            .compression_method(CompressionMethod::Deflated)
+            .large_file(true);
+        for (name, length) in files {
+            let name = name.replace('\\', "/");
+            if name.split('/').any(|part| part == "..") {
+                return Err(format!("unexpected path in save query output: {}", name).into());
+            }
+            let mut file = File::open(worlds_dir.join(&name)).map_err(|e| format!("{}: {}", name, e))?;
+            zip.start_file(name.as_str(), options)?;
+            let copied = io::copy(&mut (&mut file).take(*length), &mut zip)?;
+            if copied < *length {
+                return Err(format!("{} is shorter than reported ({} < {})", name, copied, length).into());
+            }
+        }
+        zip.finish()?.sync_all()?;
+        Ok(())
+    })();
+    if let Err(e) = result {
+        let _ = fs::remove_file(&partial);
+        return Err(e);
+    }
+    fs::rename(&partial, path)?;
+    Ok(fs::metadata(path)?.len())
+}
+
+/// zip を展開し、含まれているワールド (最上位のフォルダ) の名前を返す
+fn extract_zip(path: &Path, dir: &Path) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>> {
+    let mut archive = ZipArchive::new(File::open(path)?)?;
+    let mut worlds = Vec::new();
+    for i in 0..archive.len() {
+        let file = archive.by_index(i)?;
+        let name = file.enclosed_name().ok_or_else(|| format!("unsafe path in backup: {}", file.name()))?;
+        if let Some(std::path::Component::Normal(world)) = name.components().next() {
+            let world = world.to_string_lossy().into_owned();
+            if name.components().count() > 1 && !worlds.contains(&world) {
+                worlds.push(world);
+            }
+        }
+    }
+    if worlds.is_empty() {
+        return Err("backup does not contain any world".into());
+    }
+    let _ = fs::remove_dir_all(dir);
+    archive.extract(dir)?;
+    Ok(worlds)
+}
+
+/// 展開したワールドを worlds に移す。失敗したら元のワールドを戻す
+fn swap_worlds(
+    worlds_dir: &Path,
+    staging: &Path,
+    worlds: &[String],
+    suffix: &str,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let mut done: Vec<(PathBuf, Option<PathBuf>)> = Vec::new();
+    for world in worlds {
+        let target = worlds_dir.join(world);
+        let safety = worlds_dir.join(format!("{}.{}", world, suffix));
+        let result = (|| -> io::Result<Option<PathBuf>> {
+            let moved = if target.exists() {
+                fs::rename(&target, &safety)?;
+                Some(safety.clone())
+            } else {
+                None
+            };
+            if let Err(e) = fs::rename(staging.join(world), &target) {
+                if let Some(safety) = &moved {
+                    let _ = fs::rename(safety, &target);
+                }
+                return Err(e);
+            }
+            Ok(moved)
+        })();
+        match result {
+            Ok(moved) => done.push((target, moved)),
+            Err(e) => {
+                for (target, moved) in done.into_iter().rev() {
+                    let _ = fs::remove_dir_all(&target);
+                    if let Some(safety) = moved {
+                        let _ = fs::rename(safety, &target);
+                    }
+                }
+                return Err(format!("{}: {}", world, e).into());
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn backup(id: &str, created_at: DateTime<Local>) -> Backup {
+        Backup { id: id.to_string(), path: PathBuf::from(id), size: 0, created_at }
+    }
+
+    fn ids(keep: HashSet<String>) -> Vec<String> {
+        let mut ids: Vec<String> = keep.into_iter().collect();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn parses_world_names_with_commas() {
+        let line = "Alpha, Beta/db/000005.ldb:1234, Alpha, Beta/level.dat:2048, Alpha, Beta/levelname.txt:11";
+        assert_eq!(
+            parse_file_list(line),
+            Some(vec![
+                ("Alpha, Beta/db/000005.ldb".to_string(), 1234),
+                ("Alpha, Beta/level.dat".to_string(), 2048),
+                ("Alpha, Beta/levelname.txt".to_string(), 11),
+            ])
+        );
+    }
+
+    #[test]
+    fn parses_file_names_with_commas() {
+        let line = "Bedrock level/db/a, b.ldb:1, Bedrock level/level.dat:2\r";
+        assert_eq!(
+            parse_file_list(line),
+            Some(vec![("Bedrock level/db/a, b.ldb".to_string(), 1), ("Bedrock level/level.dat".to_string(), 2)])
+        );
+    }
+
+    #[test]
+    fn ignores_lines_that_are_not_file_lists() {
+        assert_eq!(parse_file_list("Data saved. Files are now ready to be copied."), None);
+        assert_eq!(parse_file_list("Saving..."), None);
+        assert_eq!(parse_file_list("level.dat:2048"), None);
+        assert_eq!(parse_file_list("Bedrock level/level.dat:2048, trailing text"), None);
+        assert_eq!(parse_file_list(""), None);
+    }
+
+    #[test]
+    fn keep_last_zero_keeps_only_the_newest() {
+        let now = Local.with_ymd_and_hms(2026, 10, 18, 12, 0, 0).unwrap();
+        let backups = [backup("c", now), backup("b", now - ChronoDuration::hours(1)), backup("a", now - ChronoDuration::days(1))];
+        let retention = Retention { last: Some(0), daily: None, weekly: None };
+        assert_eq!(ids(retention.keep(&backups, now)), ["c"]);
+    }
+
+    #[test]
+    fn keep_last_zero_with_daily_keeps_one_per_day() {
+        let now = Local.with_ymd_and_hms(2026, 10, 18, 12, 0, 0).unwrap();
+        let backups = [
+            backup("e", now),
+            backup("d", now - ChronoDuration::hours(1)),
+            backup("c", now - ChronoDuration::days(1)),
+            backup("b", now - ChronoDuration::days(2)),
+            backup("a", now - ChronoDuration::days(5)),
+        ];
+        let retention = Retention { last: Some(0), daily: Some(3), weekly: None };
+        assert_eq!(ids(retention.keep(&backups, now)), ["b", "c", "e"]);
+    }
+
+    #[test]
+    fn keep_last_zero_with_weekly_keeps_one_per_week() {
+        // 2026-10-18 は日曜日 (ISO 週の最後の日)
+        let now = Local.with_ymd_and_hms(2026, 10, 18, 12, 0, 0).unwrap();
+        let backups = [
+            backup("d", now),
+            backup("c", now - ChronoDuration::days(2)),
+            backup("b", now - ChronoDuration::days(8)),
+            backup("a", now - ChronoDuration::days(30)),
+        ];
+        let retention = Retention { last: Some(0), daily: None, weekly: Some(2) };
+        assert_eq!(ids(retention.keep(&backups, now)), ["b", "d"]);
+    }
+
+    #[test]
+    fn huge_retention_periods_do_not_overflow() {
+        let now = Local.with_ymd_and_hms(2026, 10, 18, 12, 0, 0).unwrap();
+        let backups = [backup("b", now), backup("a", now - ChronoDuration::days(400))];
+        let retention = Retention { last: Some(0), daily: Some(u32::MAX), weekly: Some(u32::MAX) };
+        assert_eq!(ids(retention.keep(&backups, now)), ["a", "b"]);
+    }
+}
@@ -0,0 +1,125 @@
+use crate::allowlist::Allowlist;
+use crate::server_controller::ServerController;
+use crate::store::{load_json, save_json};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serenity::all::UserId;
+use std::env;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Ban {
+    pub gamertag: String,
+    /// /server でこのゲーマータグを登録したDiscordユーザー
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<UserId>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    pub banned_by: UserId,
+    pub banned_at: DateTime<Utc>,
+    /// None なら無期限
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl Ban {
+    fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_none_or(|expires_at| expires_at > now)
+    }
+}
+
+/// BAN したプレイヤー。期限が切れるまで allowlist への登録を拒否する
+pub struct BanList {
+    path: PathBuf,
+    bans: Mutex<Vec<Ban>>,
+    allowlist: Allowlist,
+    server_controller: Arc<ServerController>,
+}
+
+impl BanList {
+    /// BANS_PATH (既定は bans.json) に保存する
+    pub fn from_env(server_controller: Arc<ServerController>) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let path = PathBuf::from(env::var("BANS_PATH").unwrap_or_else(|_| "bans.json".to_string()));
+        let bans = load_json(&path)?;
+        Ok(Self {
+            path,
+            bans: Mutex::new(bans),
+            allowlist: Allowlist::from_env(&server_controller),
+            server_controller,
+        })
+    }
+
+    /// 接続中ならキックする (サーバーが止まっていれば何もしない)
+    pub fn kick(&self, gamertag: &str, reason: Option<&str>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if !self.server_controller.is_running() {
+            return Ok(());
+        }
+        self.server_controller.kick(gamertag, reason)
+    }
+
+    /// BAN を記録し、キックして allowlist から削除する。同じゲーマータグの BAN は置き換える。
+    /// キックに失敗しても allowlist からは削除する
+    pub fn ban(
+        &self,
+        gamertag: &str,
+        user_id: Option<UserId>,
+        duration: Option<Duration>,
+        reason: Option<&str>,
+        banned_by: UserId,
+    ) -> Result<Ban, Box<dyn std::error::Error + Send + Sync>> {
+        let now = Utc::now();
+        let expires_at = match duration {
+            // 書き込む前に、期限が表せない長さなら断る
+            Some(duration) => Some(
+                now.checked_add_signed(chrono::Duration::from_std(duration)?)
+                    .ok_or("ban duration is too long")?,
+            ),
+            None => None,
+        };
+        let ban = Ban {
+            gamertag: gamertag.to_string(),
+            user_id,
+            reason: reason.map(str::to_string),
+            banned_by,
+            banned_at: now,
+            expires_at,
+        };
+        {
+            let mut bans = self.bans.lock().unwrap();
+            bans.retain(|ban| ban.is_active(now) && !ban.gamertag.eq_ignore_ascii_case(gamertag));
+            bans.push(ban.clone());
+            save_json(&self.path, &*bans)?;
+        }
+        let kicked = self.kick(gamertag, reason);
+        self.allowlist.remove(&[gamertag.to_string()], &self.server_controller)?;
+        kicked?;
+        println!("Banned {} (until {:?})", gamertag, expires_at);
+        Ok(ban)
+    }
+
+    /// BAN を解除する。なければ false
+    pub fn unban(&self, gamertag: &str) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        let now = Utc::now();
+        let mut bans = self.bans.lock().unwrap();
+        let before = bans.len();
+        bans.retain(|ban| !ban.gamertag.eq_ignore_ascii_case(gamertag));
+        let removed = bans.len() != before;
+        bans.retain(|ban| ban.is_active(now));
+        save_json(&self.path, &*bans)?;
+        Ok(removed)
+    }
+
+    /// ゲーマータグか Discord ユーザーに有効な BAN があればそれを返す
+    pub fn find(&self, gamertag: &str, user_id: Option<UserId>) -> Option<Ban> {
+        let now = Utc::now();
+        self.bans
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|ban| ban.is_active(now))
+            .find(|ban| ban.gamertag.eq_ignore_ascii_case(gamertag) || (user_id.is_some() && ban.user_id == user_id))
+            .cloned()
+    }
+}
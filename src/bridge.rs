@@ -0,0 +1,128 @@
+use crate::i18n::{tr, Language};
+use crate::log_parser::{parse_line, ServerEvent};
+use crate::server_controller::ServerController;
+use serenity::all::{
+    ChannelId, Context, CreateAllowedMentions, CreateWebhook, ExecuteWebhook, Http, Message, Webhook,
+};
+use std::env;
+use std::sync::Arc;
+use tokio::sync::broadcast::{self, error::RecvError};
+
+const WEBHOOK_NAME: &str = "Minecraft Chat Bridge";
+const SERVER_DISPLAY_NAME: &str = "Minecraft";
+/// ゲーム内に送るメッセージの最大文字数
+const MAX_GAME_MESSAGE_CHARS: usize = 256;
+
+/// Discordのチャンネルとゲーム内チャットを相互に中継する
+pub struct ChatBridge {
+    channel_id: ChannelId,
+    server_controller: Arc<ServerController>,
+}
+
+impl ChatBridge {
+    /// BRIDGE_CHANNEL_ID が未設定なら None
+    pub fn from_env(server_controller: Arc<ServerController>) -> Result<Option<Self>, Box<dyn std::error::Error + Send + Sync>> {
+        let Ok(channel_id) = env::var("BRIDGE_CHANNEL_ID") else {
+            return Ok(None);
+        };
+        let channel_id = channel_id
+            .parse::<u64>()
+            .map_err(|_| "BRIDGE_CHANNEL_ID must be a valid u64")?;
+        Ok(Some(Self {
+            channel_id: ChannelId::new(channel_id),
+            server_controller,
+        }))
+    }
+
+    pub fn channel_id(&self) -> ChannelId {
+        self.channel_id
+    }
+
+    /// ゲーム → Discord の中継を開始する
+    pub fn start(self: &Arc<Self>, http: Arc<Http>) {
+        let bridge = Arc::clone(self);
+        let output = self.server_controller.subscribe_output();
+        tokio::spawn(async move {
+            bridge.relay_to_discord(http, output).await;
+        });
+    }
+
+    async fn relay_to_discord(&self, http: Arc<Http>, mut output: broadcast::Receiver<String>) {
+        let webhook = match self.find_or_create_webhook(&http).await {
+            Ok(webhook) => webhook,
+            Err(e) => {
+                eprintln!("Failed to set up chat bridge webhook: {}", e);
+                return;
+            }
+        };
+        let lang = Language::from_env();
+        loop {
+            let line = match output.recv().await {
+                Ok(line) => line,
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break,
+            };
+            let (username, content) = match parse_line(&line) {
+                Some(ServerEvent::Chat { name, message }) => (name, message),
+                Some(ServerEvent::PlayerConnected { name, .. }) => {
+                    (SERVER_DISPLAY_NAME.to_string(), tr!(lang, "player-joined", player = name))
+                }
+                Some(ServerEvent::PlayerDisconnected { name, .. }) => {
+                    (SERVER_DISPLAY_NAME.to_string(), tr!(lang, "player-left", player = name))
+                }
+                Some(ServerEvent::Death { message }) => {
+                    (SERVER_DISPLAY_NAME.to_string(), tr!(lang, "bridge-death", message = message))
+                }
+                None => continue,
+            };
+            let builder = ExecuteWebhook::new()
+                .username(username)
+                .content(content)
+                .allowed_mentions(CreateAllowedMentions::new());
+            if let Err(e) = webhook.execute(&*http, false, builder).await {
+                eprintln!("Failed to relay chat to Discord: {}", e);
+            }
+        }
+    }
+
+    async fn find_or_create_webhook(&self, http: &Http) -> Result<Webhook, Box<dyn std::error::Error + Send + Sync>> {
+        let existing = self
+            .channel_id
+            .webhooks(http)
+            .await?
+            .into_iter()
+            .find(|webhook| webhook.name.as_deref() == Some(WEBHOOK_NAME) && webhook.token.is_some());
+        match existing {
+            Some(webhook) => Ok(webhook),
+            None => Ok(self.channel_id.create_webhook(http, CreateWebhook::new(WEBHOOK_NAME)).await?),
+        }
+    }
+
+    /// Discord → ゲームの中継
+    pub async fn handle_message(&self, ctx: &Context, msg: &Message) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let name = msg
+            .member
+            .as_ref()
+            .and_then(|member| member.nick.clone())
+            .unwrap_or_else(|| msg.author.display_name().to_string());
+        let mut content = msg.content_safe(&ctx.cache).replace(['\r', '\n'], " ");
+        if !msg.attachments.is_empty() {
+            content.push_str(" [attachment]");
+        }
+        let content = content.trim();
+        if content.is_empty() {
+            return Ok(());
+        }
+        let content: String = content.chars().take(MAX_GAME_MESSAGE_CHARS).collect();
+        self.server_controller.send_command(&tellraw_command(&name, &content))?;
+        Ok(())
+    }
+}
+
+/// JSONのエスケープは serde_json に任せる
+fn tellraw_command(name: &str, message: &str) -> String {
+    let rawtext = serde_json::json!({
+        "rawtext": [{ "text": format!("§9[Discord]§r <{}> {}", name, message) }]
+    });
+    format!("tellraw @a {}", rawtext)
+}
@@ -1,35 +1,389 @@
 use serenity::all::{
-    CommandInteraction, Context, CreateCommand, CreateInteractionResponse,
-    CreateInteractionResponseMessage, CreateActionRow, CreateInputText, InputTextStyle,
-    CreateModal, ModalInteraction,
+    Attachment, ButtonStyle, CommandInteraction, CommandOptionType, ComponentInteraction, Context, CreateButton,
+    CreateCommand, CreateCommandOption, CreateInteractionResponse, CreateInteractionResponseMessage,
+    CreateActionRow, CreateInputText, EditInteractionResponse, InputTextStyle, CreateModal,
+    CreateMessage, ModalInteraction, ResolvedOption, ResolvedValue, UserId,
 };
-use crate::server_controller::ServerController;
+use crate::addons::{self, Pack};
+use crate::audit::{AuditEntry, AuditQuery};
+use crate::bans::Ban;
+use crate::confirm::{ConfirmError, CONFIRM_TIMEOUT};
+use crate::duration::{format_duration, parse_duration};
+use crate::i18n::{tr, Language};
+use crate::maintenance::{MaintenanceInfo, MaintenanceMode};
+use crate::permissions::Permission;
+use crate::playtime::Period;
+use crate::properties::InvalidValue;
+use crate::prune::MAX_PRUNE_DAYS;
+use crate::restart::MAX_RESTART_DELAY;
+use crate::resources::ResourceMonitor;
+use crate::server_controller::ServerState;
+use crate::size::format_size;
+use crate::state::AppState;
+use crate::updater::UpdateSource;
+use crate::worlds;
+use chrono::{Local, Utc};
+use std::collections::BTreeMap;
+use std::future::Future;
 use std::sync::Arc;
-use std::env;
+use std::time::{Duration, Instant};
 
-pub fn register(name: &str, description: &str) -> CreateCommand {
-    CreateCommand::new(name).description(description)
+const LEADERBOARD_SIZE: usize = 10;
+const AUDIT_SEARCH_LIMIT: usize = 15;
+/// Discordのメッセージ上限 (2000文字) に余裕を持たせた長さ
+const MAX_MESSAGE_LEN: usize = 1900;
+
+/// 全言語の (Discordのロケール, 翻訳) の組
+fn localizations(id: &str) -> Vec<(&'static str, String)> {
+    Language::ALL
+        .into_iter()
+        .flat_map(|lang| lang.discord_locales().iter().map(move |locale| (*locale, tr!(lang, id))))
+        .collect()
+}
+
+pub fn register(name: &str, description_id: &str) -> CreateCommand {
+    let mut command = CreateCommand::new(name).description(tr!(Language::from_env(), description_id));
+    for (locale, description) in localizations(description_id) {
+        command = command.description_localized(locale, description);
+    }
+    command
+}
+
+pub fn option(kind: CommandOptionType, name: &str, description_id: &str) -> CreateCommandOption {
+    let mut option = CreateCommandOption::new(kind, name, tr!(Language::from_env(), description_id));
+    for (locale, description) in localizations(description_id) {
+        option = option.description_localized(locale, description);
+    }
+    option
+}
+
+/// 選択肢を追加する。表示名は `name_id` を翻訳したもの
+pub fn choice(option: CreateCommandOption, value: &str, name_id: &str) -> CreateCommandOption {
+    option.add_string_choice_localized(tr!(Language::from_env(), name_id), value, localizations(name_id))
+}
+
+/// サブコマンドの中も含めてオプションを探す
+fn find_option<'a>(options: Vec<ResolvedOption<'a>>, name: &str) -> Option<ResolvedValue<'a>> {
+    for option in options {
+        if option.name == name {
+            return Some(option.value);
+        }
+        if let ResolvedValue::SubCommand(nested) | ResolvedValue::SubCommandGroup(nested) = option.value {
+            if let Some(value) = find_option(nested, name) {
+                return Some(value);
+            }
+        }
+    }
+    None
+}
+
+fn string_option<'a>(interaction: &'a CommandInteraction, name: &str) -> Option<&'a str> {
+    match find_option(interaction.data.options(), name)? {
+        ResolvedValue::String(value) => Some(value),
+        _ => None,
+    }
+}
+
+fn integer_option(interaction: &CommandInteraction, name: &str) -> Option<i64> {
+    match find_option(interaction.data.options(), name)? {
+        ResolvedValue::Integer(value) => Some(value),
+        _ => None,
+    }
+}
+
+fn user_option(interaction: &CommandInteraction, name: &str) -> Option<UserId> {
+    match find_option(interaction.data.options(), name)? {
+        ResolvedValue::User(user, _) => Some(user.id),
+        _ => None,
+    }
+}
+
+fn attachment_option<'a>(interaction: &'a CommandInteraction, name: &str) -> Option<&'a Attachment> {
+    match find_option(interaction.data.options(), name)? {
+        ResolvedValue::Attachment(attachment) => Some(attachment),
+        _ => None,
+    }
+}
+
+/// `/config set` で受け付けなかった値の返信
+fn invalid_value_message(lang: Language, key: &str, invalid: &InvalidValue) -> String {
+    match invalid {
+        InvalidValue::UnknownKey => tr!(lang, "config-unknown-key", key = key),
+        InvalidValue::MultiLine => tr!(lang, "config-invalid-single-line"),
+        InvalidValue::NotBool => tr!(lang, "config-invalid-bool", key = key),
+        InvalidValue::OutOfRange { min, max } => tr!(lang, "config-invalid-range", key = key, min = *min, max = *max),
+        InvalidValue::NotChoice(choices) => tr!(lang, "config-invalid-choice", key = key, choices = choices.join(", ")),
+    }
+}
+
+/// ダウンロードする前に添付ファイルの大きさを確かめ、大きすぎれば返信する文を返す
+fn too_large(lang: Language, attachment: &Attachment, max: u64) -> Option<String> {
+    (u64::from(attachment.size) > max).then(|| {
+        tr!(
+            lang,
+            "upload-too-large",
+            file = attachment.filename.as_str(),
+            size = format_size(attachment.size.into()),
+            max = format_size(max),
+        )
+    })
+}
+
+fn bool_option(interaction: &CommandInteraction, name: &str) -> Option<bool> {
+    match find_option(interaction.data.options(), name)? {
+        ResolvedValue::Boolean(value) => Some(value),
+        _ => None,
+    }
+}
+
+/// 最初のサブコマンド名
+fn subcommand(interaction: &CommandInteraction) -> Option<&str> {
+    interaction.data.options().into_iter().find_map(|option| match option.value {
+        ResolvedValue::SubCommand(_) => Some(option.name),
+        _ => None,
+    })
+}
+
+/// ADMIN_USER_IDS / ADMIN_ROLE_IDS に含まれるか、Discordサーバーの管理者権限を持つか
+fn is_admin(state: &AppState, interaction: &CommandInteraction) -> bool {
+    let member = interaction.member.as_deref();
+    let roles = member.map(|member| member.roles.as_slice()).unwrap_or_default();
+    state.admins.contains(interaction.user.id, roles)
+        || member
+            .and_then(|member| member.permissions)
+            .is_some_and(|permissions| permissions.administrator())
+}
+
+/// `gamertag` オプション、なければ `user` オプションのユーザーが /server で登録したゲーマータグ
+fn target_gamertag(state: &AppState, interaction: &CommandInteraction) -> Option<String> {
+    match string_option(interaction, "gamertag").map(str::trim).filter(|gamertag| !gamertag.is_empty()) {
+        Some(gamertag) => Some(gamertag.to_string()),
+        None => state.links.gamertag_for(user_option(interaction, "user")?),
+    }
+}
+
+/// サブコマンドを含めたコマンド名 ("allowlist prune" など)
+fn command_path(interaction: &CommandInteraction) -> String {
+    match subcommand(interaction) {
+        Some(subcommand) => format!("{} {}", interaction.data.name, subcommand),
+        None => interaction.data.name.clone(),
+    }
+}
+
+/// 監査ログに残すオプションの値 (サブコマンドの中も含む)
+fn option_arguments(options: Vec<ResolvedOption<'_>>, arguments: &mut BTreeMap<String, String>) {
+    for option in options {
+        let value = match option.value {
+            ResolvedValue::SubCommand(nested) | ResolvedValue::SubCommandGroup(nested) => {
+                option_arguments(nested, arguments);
+                continue;
+            }
+            ResolvedValue::String(value) => value.to_string(),
+            ResolvedValue::Integer(value) => value.to_string(),
+            ResolvedValue::Number(value) => value.to_string(),
+            ResolvedValue::Boolean(value) => value.to_string(),
+            ResolvedValue::User(user, _) => format!("<@{}>", user.id),
+            ResolvedValue::Attachment(attachment) => attachment.filename.clone(),
+            _ => continue,
+        };
+        arguments.insert(option.name.to_string(), value);
+    }
+}
+
+/// 実行したユーザー・コマンド・引数と結果を監査ログに記録する
+fn audit(state: &AppState, interaction: &CommandInteraction, result: Result<String, String>) {
+    let mut arguments = BTreeMap::new();
+    option_arguments(interaction.data.options(), &mut arguments);
+    state.audit.record(AuditEntry::new(Some(interaction.user.id), &command_path(interaction), arguments, result));
+}
+
+/// 管理者でないユーザーの実行を記録して断る
+async fn deny(
+    ctx: &Context,
+    interaction: &CommandInteraction,
+    state: &AppState,
+    lang: Language,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    audit(state, interaction, Err("permission denied".to_string()));
+    reply(ctx, interaction, tr!(lang, "not-admin"), true).await
+}
+
+/// 1行ずつ追加し、Discordの文字数制限を超える分は省略する
+fn join_lines(header: String, lines: Vec<String>) -> String {
+    let mut msg = header;
+    for (i, line) in lines.iter().enumerate() {
+        if msg.len() + line.len() + 1 > MAX_MESSAGE_LEN {
+            msg.push_str(&format!("\n… (+{})", lines.len() - i));
+            break;
+        }
+        msg.push('\n');
+        msg.push_str(line);
+    }
+    msg
+}
+
+/// パック一覧の1行
+fn pack_line(lang: Language, pack: &Pack) -> String {
+    let kind = tr!(lang, &format!("addon-kind-{}", pack.kind.name()));
+    let entry_id = if pack.dir.is_some() { "addon-list-entry" } else { "addon-list-entry-missing" };
+    tr!(lang, entry_id, name = pack.name.as_str(), version = pack.version_string(), kind = kind, uuid = pack.uuid.as_str())
+}
+
+/// BAN の期限 (無期限なら「無期限」)
+fn ban_expiry(lang: Language, ban: &Ban) -> String {
+    match ban.expires_at {
+        Some(expires_at) => format!("<t:{}:F>", expires_at.timestamp()),
+        None => tr!(lang, "ban-permanent"),
+    }
+}
+
+fn rate_limited_message(lang: Language, wait: Duration) -> String {
+    let at = Utc::now() + chrono::Duration::from_std(wait).unwrap_or_default() + chrono::Duration::seconds(1);
+    tr!(lang, "rate-limited", time = format!("<t:{}:R>", at.timestamp()))
+}
+
+async fn reply(
+    ctx: &Context,
+    interaction: &CommandInteraction,
+    content: String,
+    ephemeral: bool,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    interaction
+        .create_response(
+            &ctx.http,
+            CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new().content(content).ephemeral(ephemeral)
+            )
+        )
+        .await?;
+    Ok(())
+}
+
+/// 確認ボタンを押したあとは確認メッセージが最初の応答なので、それを結果で書き換える
+async fn respond(
+    ctx: &Context,
+    interaction: &CommandInteraction,
+    content: String,
+    ephemeral: bool,
+    confirmed: bool,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    if !confirmed {
+        return reply(ctx, interaction, content, ephemeral).await;
+    }
+    interaction
+        .edit_response(&ctx.http, EditInteractionResponse::new().content(content))
+        .await?;
+    Ok(())
+}
+
+/// 起動・停止を待つあいだ、サーバーの状態が変わるたびに返信を書き換える
+async fn follow_server_state(
+    ctx: &Context,
+    interaction: &CommandInteraction,
+    state: &AppState,
+    lang: Language,
+    task: impl Future<Output = Result<(), Box<dyn std::error::Error + Send + Sync>>>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let mut server_state = state.server_controller.subscribe_state();
+    server_state.mark_unchanged();
+    tokio::pin!(task);
+    loop {
+        tokio::select! {
+            result = &mut task => return result,
+            changed = server_state.changed() => {
+                if changed.is_err() {
+                    return task.await;
+                }
+                let id = match *server_state.borrow_and_update() {
+                    ServerState::Stopped => "server-state-stopped",
+                    ServerState::Starting => "server-state-starting",
+                    ServerState::Running => "server-state-running",
+                    ServerState::Stopping => "server-state-stopping",
+                };
+                // 返信を書き換えられなくても、起動・停止は最後まで待つ
+                if let Err(e) = interaction
+                    .edit_response(&ctx.http, EditInteractionResponse::new().content(tr!(lang, id)))
+                    .await
+                {
+                    eprintln!("Failed to update the reply: {}", e);
+                }
+            }
+        }
+    }
+}
+
+/// 取り消せない操作の前に、実行した本人だけが押せる確認ボタンを出す
+async fn ask_confirmation(
+    ctx: &Context,
+    interaction: &CommandInteraction,
+    state: &AppState,
+    lang: Language,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let id = state.confirmations.request(interaction);
+    let expires_at = Utc::now() + chrono::Duration::from_std(CONFIRM_TIMEOUT)?;
+    let mut arguments = BTreeMap::new();
+    option_arguments(interaction.data.options(), &mut arguments);
+    let command = std::iter::once(format!("/{}", command_path(interaction)))
+        .chain(arguments.iter().map(|(key, value)| format!("{}:{}", key, value)))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let buttons = CreateActionRow::Buttons(vec![
+        CreateButton::new(format!("confirm:{}", id))
+            .label(tr!(lang, "confirm-button"))
+            .style(ButtonStyle::Danger),
+        CreateButton::new(format!("cancel:{}", id))
+            .label(tr!(lang, "cancel-button"))
+            .style(ButtonStyle::Secondary),
+    ]);
+    interaction
+        .create_response(
+            &ctx.http,
+            CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new()
+                    .content(tr!(
+                        lang,
+                        "confirm-prompt",
+                        command = command,
+                        expires = format!("<t:{}:R>", expires_at.timestamp()),
+                    ))
+                    .components(vec![buttons])
+                    .ephemeral(true)
+            )
+        )
+        .await?;
+    Ok(())
 }
 
 pub async fn handle_command(
     ctx: &Context, 
     interaction: &CommandInteraction, 
-    server_controller: Arc<ServerController>
+    state: &AppState
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let lang = Language::for_locale(&interaction.locale);
+    // /server はフォームを開くだけなので、コマンドごとの制限はフォームの送信で数える
+    let command_key = (interaction.data.name != "server").then_some(interaction.data.name.as_str());
+    if let Err(wait) = state.rate_limiter.check(interaction.user.id, command_key) {
+        return reply(ctx, interaction, rate_limited_message(lang, wait), true).await;
+    }
+    run_command(ctx, interaction, state, false).await
+}
+
+/// `confirmed` は確認ボタンが押されたあとの実行かどうか。
+/// 取り消せない操作は、入力と権限を確かめてから確認を求める
+async fn run_command(
+    ctx: &Context,
+    interaction: &CommandInteraction,
+    state: &AppState,
+    confirmed: bool,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let lang = env::var("LANGUAGE").unwrap_or_else(|_| "JP".to_string());
-    let is_en = lang.to_uppercase() == "EN";
+    let lang = Language::for_locale(&interaction.locale);
     match interaction.data.name.as_str() {
         "server" => {
-            let (title, label, placeholder) = if is_en {
-                ("Server Registration", "Game ID", "Enter your Game ID")
-            } else {
-                ("サーバー登録", "ゲームID", "ゲームIDを入力してください")
-            };
-            let modal = CreateModal::new("server_modal", title)
+            let modal = CreateModal::new("server_modal", tr!(lang, "server-modal-title"))
                 .components(vec![
                     CreateActionRow::InputText(
-                        CreateInputText::new(InputTextStyle::Short, label, "game_id")
-                            .placeholder(placeholder)
+                        CreateInputText::new(InputTextStyle::Short, tr!(lang, "server-modal-label"), "game_id")
+                            .placeholder(tr!(lang, "server-modal-placeholder"))
                             .required(true)
                     )
                 ]);
@@ -38,39 +392,1014 @@ pub async fn handle_command(
                 .create_response(&ctx.http, CreateInteractionResponse::Modal(modal))
                 .await?;
         }
+        "start" => {
+            if !is_admin(state, interaction) {
+                return deny(ctx, interaction, state, lang).await;
+            }
+            if state.maintenance.keeps_stopped() {
+                return reply(ctx, interaction, tr!(lang, "maintenance-refused"), true).await;
+            }
+            if state.server_controller.state() != ServerState::Stopped {
+                return reply(ctx, interaction, tr!(lang, "start-already-running"), true).await;
+            }
+            reply(ctx, interaction, tr!(lang, "start-in-progress"), false).await?;
+            let started = Instant::now();
+            let result = follow_server_state(ctx, interaction, state, lang, state.lifecycle.start()).await;
+            audit(state, interaction, result.as_ref().map(|_| "started".to_string()).map_err(|e| e.to_string()));
+            let msg = match result {
+                Ok(()) => tr!(lang, "start-done", duration = format_duration(started.elapsed())),
+                Err(e) => {
+                    eprintln!("Failed to start server: {}", e);
+                    tr!(lang, "start-failed", error = e.to_string())
+                }
+            };
+            interaction
+                .edit_response(&ctx.http, EditInteractionResponse::new().content(msg))
+                .await?;
+        }
+        "stop" => {
+            if !is_admin(state, interaction) {
+                return deny(ctx, interaction, state, lang).await;
+            }
+            if state.server_controller.state() == ServerState::Stopped {
+                return respond(ctx, interaction, tr!(lang, "stop-not-running"), true, confirmed).await;
+            }
+            if !confirmed {
+                return ask_confirmation(ctx, interaction, state, lang).await;
+            }
+            respond(ctx, interaction, tr!(lang, "stop-in-progress"), true, confirmed).await?;
+            let result = follow_server_state(ctx, interaction, state, lang, state.lifecycle.stop()).await;
+            audit(state, interaction, result.as_ref().map(|_| "stopped".to_string()).map_err(|e| e.to_string()));
+            let msg = match result {
+                Ok(()) => tr!(lang, "stop-done"),
+                Err(e) => {
+                    eprintln!("Failed to stop server: {}", e);
+                    tr!(lang, "stop-failed", error = e.to_string())
+                }
+            };
+            interaction
+                .edit_response(&ctx.http, EditInteractionResponse::new().content(msg))
+                .await?;
+        }
         "restart" => {
-            // -------------------------
-            // 現在restartコマンドの実行権限は限定されていません、everyoneに実行できるようになっています
-            // あなたがもしこのコードをそのまま使用する場合は **絶対に** restartを削除するか権限を限定するようにコードを編集してください
-            // -------------------------
-            let msg = if is_en { "🔄 Restarting server..." } else { "🔄 サーバーを再起動しています..." };
+            if !is_admin(state, interaction) {
+                return deny(ctx, interaction, state, lang).await;
+            }
+            if bool_option(interaction, "cancel").unwrap_or(false) {
+                let msg = if state.restart.cancel() {
+                    audit(state, interaction, Ok("scheduled restart cancelled".to_string()));
+                    tr!(lang, "restart-cancelled")
+                } else {
+                    tr!(lang, "restart-nothing-to-cancel")
+                };
+                return reply(ctx, interaction, msg, false).await;
+            }
+            if state.maintenance.keeps_stopped() {
+                return respond(ctx, interaction, tr!(lang, "maintenance-refused"), true, confirmed).await;
+            }
+            let delay = integer_option(interaction, "delay").map(|minutes| Duration::from_secs((minutes.max(1) as u64).saturating_mul(60)));
+            if delay.is_some_and(|delay| delay > MAX_RESTART_DELAY) {
+                let msg = tr!(lang, "restart-delay-too-long", max = format_duration(MAX_RESTART_DELAY));
+                return respond(ctx, interaction, msg, true, confirmed).await;
+            }
+            if !confirmed {
+                return ask_confirmation(ctx, interaction, state, lang).await;
+            }
+            if let Some(delay) = delay {
+                let result = state.restart.schedule_in(delay);
+                audit(state, interaction, result.as_ref().map(|at| format!("restart scheduled at {}", at.to_rfc3339())).map_err(|e| e.to_string()));
+                let msg = match result {
+                    Ok(at) => tr!(lang, "restart-scheduled", time = format!("<t:{}:R>", at.timestamp())),
+                    Err(e) => tr!(lang, "restart-failed", error = e.to_string()),
+                };
+                return respond(ctx, interaction, msg, false, confirmed).await;
+            }
+            respond(ctx, interaction, tr!(lang, "restart-in-progress"), false, confirmed).await?;
+            let result = state.restart.restart_now().await;
+            audit(state, interaction, result.as_ref().map(|_| "restarted".to_string()).map_err(|e| e.to_string()));
+            if let Err(e) = result {
+                eprintln!("Failed to restart server: {}", e);
+                interaction
+                    .edit_response(
+                        &ctx.http,
+                        EditInteractionResponse::new().content(tr!(lang, "restart-failed", error = e.to_string())),
+                    )
+                    .await?;
+            }
+        }
+        "players" => {
+            let players = state.players.online().await;
+            let mut msg = tr!(lang, "players-title", count = players.len());
+            if players.is_empty() {
+                msg.push('\n');
+                msg.push_str(&tr!(lang, "players-empty"));
+            }
+            for player in players {
+                let online_for = (Local::now() - player.since).to_std().unwrap_or_default();
+                msg.push('\n');
+                msg.push_str(&tr!(
+                    lang,
+                    "players-entry",
+                    name = player.name,
+                    duration = format_duration(online_for),
+                ));
+            }
+            reply(ctx, interaction, msg, true).await?;
+        }
+        "stats" => {
+            let server_state = match state.server_controller.state() {
+                ServerState::Stopped => "stopped",
+                ServerState::Starting => "starting",
+                ServerState::Running => "running",
+                ServerState::Stopping => "stopping",
+            };
+            let mut lines = vec![
+                tr!(lang, "stats-state", state = tr!(lang, &format!("stats-state-{}", server_state))),
+                tr!(
+                    lang,
+                    "stats-uptime",
+                    uptime = state.server_controller.uptime().map(format_duration).unwrap_or_else(|| "-".to_string()),
+                ),
+                tr!(lang, "stats-players", players = state.players.online().await.len()),
+            ];
+            match state.server_controller.resource_usage() {
+                Some(usage) => {
+                    lines.push(tr!(lang, "stats-cpu", cpu = ResourceMonitor::format_cpu(&usage)));
+                    lines.push(tr!(lang, "stats-memory", memory = state.resources.format_memory(&usage)));
+                }
+                None => lines.push(tr!(lang, "stats-unavailable")),
+            }
+            reply(ctx, interaction, join_lines(tr!(lang, "stats-title"), lines), true).await?;
+        }
+        "playtime" => {
+            // 省略時は /server で登録したゲーマータグ
+            let player = match string_option(interaction, "player") {
+                Some(player) => player.to_string(),
+                None => match state.links.gamertag_for(interaction.user.id) {
+                    Some(gamertag) => gamertag,
+                    None => return reply(ctx, interaction, tr!(lang, "playtime-specify-player"), true).await,
+                },
+            };
+            let msg = match state.playtime.player_totals(&player).await {
+                Some((name, totals)) => {
+                    let mut msg = tr!(lang, "playtime-title", player = name);
+                    for (period, total) in totals {
+                        msg.push('\n');
+                        msg.push_str(&tr!(
+                            lang,
+                            "playtime-line",
+                            period = tr!(lang, &format!("period-{}", period.name())),
+                            duration = format_duration(total),
+                        ));
+                    }
+                    msg
+                }
+                None => tr!(lang, "playtime-not-found", player = player.as_str()),
+            };
+            reply(ctx, interaction, msg, true).await?;
+        }
+        "leaderboard" => {
+            let period = string_option(interaction, "period")
+                .and_then(Period::from_name)
+                .unwrap_or(Period::All);
+            let entries = state.playtime.leaderboard(period).await;
+            let mut msg = tr!(lang, "leaderboard-title", period = tr!(lang, &format!("period-{}", period.name())));
+            if entries.is_empty() {
+                msg.push('\n');
+                msg.push_str(&tr!(lang, "leaderboard-empty"));
+            }
+            for (rank, entry) in entries.into_iter().take(LEADERBOARD_SIZE).enumerate() {
+                msg.push('\n');
+                msg.push_str(&tr!(
+                    lang,
+                    "leaderboard-entry",
+                    rank = rank + 1,
+                    name = entry.name,
+                    duration = format_duration(entry.total),
+                ));
+            }
+            reply(ctx, interaction, msg, false).await?;
+        }
+        "guest" => {
+            if !is_admin(state, interaction) {
+                return deny(ctx, interaction, state, lang).await;
+            }
+            let gamertag = string_option(interaction, "gamertag").unwrap_or_default().trim();
+            if gamertag.is_empty() {
+                return reply(ctx, interaction, tr!(lang, "server-empty-game-id"), true).await;
+            }
+            let duration_text = string_option(interaction, "duration").unwrap_or_default();
+            let duration = match parse_duration(duration_text) {
+                Ok(duration) => duration,
+                Err(_) => {
+                    return reply(ctx, interaction, tr!(lang, "guest-invalid-duration", duration = duration_text), true).await;
+                }
+            };
+            if let Some(ban) = state.bans.find(gamertag, None) {
+                return reply(ctx, interaction, tr!(lang, "ban-already", gamertag = ban.gamertag.as_str(), until = ban_expiry(lang, &ban)), true).await;
+            }
+            let result = state.guests.add(gamertag, duration, interaction.user.id);
+            audit(state, interaction, result.as_ref().map(|at| format!("expires at {}", at.to_rfc3339())).map_err(|e| e.to_string()));
+            let msg = match result {
+                Ok(expires_at) => tr!(
+                    lang,
+                    "guest-added",
+                    gamertag = gamertag,
+                    expires = format!("<t:{}:F>", expires_at.timestamp()),
+                ),
+                Err(e) => {
+                    eprintln!("Failed to add guest {}: {}", gamertag, e);
+                    tr!(lang, "guest-failed", error = e.to_string())
+                }
+            };
+            reply(ctx, interaction, msg, true).await?;
+        }
+        "allowlist" => {
+            if !is_admin(state, interaction) {
+                return deny(ctx, interaction, state, lang).await;
+            }
+            if subcommand(interaction) == Some("prune") {
+                let days = integer_option(interaction, "days").unwrap_or(1).clamp(1, MAX_PRUNE_DAYS.into()) as u32;
+                let dry_run = bool_option(interaction, "dry_run").unwrap_or(false);
+                if !dry_run && !confirmed {
+                    return ask_confirmation(ctx, interaction, state, lang).await;
+                }
+                if !confirmed {
+                    interaction.defer_ephemeral(&ctx.http).await?;
+                }
+                let result = if dry_run {
+                    state.pruner.find_inactive(days).await
+                } else {
+                    state.pruner.prune(&ctx.http, days).await
+                };
+                audit(
+                    state,
+                    interaction,
+                    result
+                        .as_ref()
+                        .map(|entries| {
+                            let names: Vec<&str> = entries.iter().map(|entry| entry.name.as_str()).collect();
+                            format!("{} entries: {}", names.len(), names.join(", "))
+                        })
+                        .map_err(|e| e.to_string()),
+                );
+                let msg = match result {
+                    Ok(entries) if entries.is_empty() => tr!(lang, "prune-none", days = days),
+                    Ok(entries) => {
+                        let title_id = if dry_run { "prune-dry-run-title" } else { "prune-done-title" };
+                        let header = tr!(lang, title_id, count = entries.len(), days = days);
+                        let lines = entries
+                            .iter()
+                            .map(|entry| tr!(
+                                lang,
+                                "prune-entry",
+                                name = entry.name.as_str(),
+                                last_seen = format!("<t:{}:R>", entry.last_seen.timestamp()),
+                            ))
+                            .collect();
+                        join_lines(header, lines)
+                    }
+                    Err(e) => {
+                        eprintln!("Failed to prune allowlist: {}", e);
+                        tr!(lang, "prune-failed", error = e.to_string())
+                    }
+                };
+                interaction
+                    .edit_response(&ctx.http, EditInteractionResponse::new().content(msg))
+                    .await?;
+            }
+        }
+        "backup" => {
+            if !is_admin(state, interaction) {
+                return deny(ctx, interaction, state, lang).await;
+            }
+            match subcommand(interaction) {
+                Some("now") => {
+                    interaction.defer_ephemeral(&ctx.http).await?;
+                    let result = state.backups.create().await;
+                    audit(state, interaction, result.as_ref().map(|backup| format!("created {}", backup.id)).map_err(|e| e.to_string()));
+                    let msg = match result {
+                        Ok(backup) => tr!(
+                            lang,
+                            "backup-created",
+                            id = backup.id,
+                            size = format_size(backup.size),
+                        ),
+                        Err(e) => {
+                            eprintln!("Backup failed: {}", e);
+                            tr!(lang, "backup-failed", error = e.to_string())
+                        }
+                    };
+                    interaction
+                        .edit_response(&ctx.http, EditInteractionResponse::new().content(msg))
+                        .await?;
+                }
+                Some("list") => {
+                    let msg = match state.backups.list() {
+                        Ok(backups) if backups.is_empty() => tr!(lang, "backup-list-empty"),
+                        Ok(backups) => {
+                            let header = tr!(lang, "backup-list-title", count = backups.len());
+                            let lines = backups
+                                .iter()
+                                .map(|backup| tr!(
+                                    lang,
+                                    "backup-list-entry",
+                                    id = backup.id.as_str(),
+                                    time = format!("<t:{}:F>", backup.created_at.timestamp()),
+                                    size = format_size(backup.size),
+                                ))
+                                .collect();
+                            join_lines(header, lines)
+                        }
+                        Err(e) => {
+                            eprintln!("Failed to list backups: {}", e);
+                            tr!(lang, "backup-failed", error = e.to_string())
+                        }
+                    };
+                    reply(ctx, interaction, msg, true).await?;
+                }
+                Some("restore") => {
+                    let id = string_option(interaction, "id").unwrap_or_default().trim();
+                    if !confirmed {
+                        return ask_confirmation(ctx, interaction, state, lang).await;
+                    }
+                    let result = state.lifecycle.restore_backup(id, state.maintenance.keeps_stopped()).await;
+                    audit(
+                        state,
+                        interaction,
+                        result.as_ref().map(|restored| format!("restored {}", restored.worlds.join(", "))).map_err(|e| e.to_string()),
+                    );
+                    let msg = match result {
+                        Ok(restored) => {
+                            let id_key = if restored.restarted { "backup-restored" } else { "backup-restored-stopped" };
+                            tr!(lang, id_key, id = id, worlds = restored.worlds.join(", "))
+                        }
+                        Err(e) => {
+                            eprintln!("Failed to restore backup {}: {}", id, e);
+                            tr!(lang, "backup-restore-failed", id = id, error = e.to_string())
+                        }
+                    };
+                    interaction
+                        .edit_response(&ctx.http, EditInteractionResponse::new().content(msg))
+                        .await?;
+                }
+                _ => {}
+            }
+        }
+        "maintenance" => {
+            if !is_admin(state, interaction) {
+                return deny(ctx, interaction, state, lang).await;
+            }
+            if subcommand(interaction) == Some("on") {
+                if state.maintenance.current().is_some() {
+                    return respond(ctx, interaction, tr!(lang, "maintenance-already-on"), true, confirmed).await;
+                }
+                let eta = match string_option(interaction, "eta") {
+                    Some(text) => match parse_duration(text)
+                        .ok()
+                        .and_then(|duration| chrono::Duration::from_std(duration).ok())
+                        .and_then(|duration| Utc::now().checked_add_signed(duration))
+                    {
+                        Some(eta) => Some(eta),
+                        None => {
+                            return reply(ctx, interaction, tr!(lang, "guest-invalid-duration", duration = text), true).await;
+                        }
+                    },
+                    None => None,
+                };
+                let mode = string_option(interaction, "mode")
+                    .and_then(MaintenanceMode::from_name)
+                    .unwrap_or(MaintenanceMode::Stop);
+                if !confirmed {
+                    return ask_confirmation(ctx, interaction, state, lang).await;
+                }
+                respond(ctx, interaction, tr!(lang, "maintenance-starting"), true, confirmed).await?;
+                let info = MaintenanceInfo {
+                    mode,
+                    reason: string_option(interaction, "message").map(str::trim).filter(|reason| !reason.is_empty()).map(str::to_string),
+                    eta,
+                    started_by: interaction.user.id,
+                    started_at: Utc::now(),
+                };
+                let result = state.maintenance.begin(info).await;
+                audit(state, interaction, result.as_ref().map(|_| format!("maintenance on ({})", mode.name())).map_err(|e| e.to_string()));
+                let msg = match result {
+                    Ok(true) => tr!(lang, "maintenance-on", mode = tr!(lang, &format!("maintenance-mode-{}", mode.name()))),
+                    Ok(false) => tr!(lang, "maintenance-already-on"),
+                    Err(e) => {
+                        eprintln!("Failed to start maintenance: {}", e);
+                        tr!(lang, "maintenance-failed", error = e.to_string())
+                    }
+                };
+                interaction
+                    .edit_response(&ctx.http, EditInteractionResponse::new().content(msg))
+                    .await?;
+            } else {
+                reply(ctx, interaction, tr!(lang, "maintenance-ending"), true).await?;
+                let result = state.maintenance.end().await;
+                let msg = match result {
+                    Ok(None) => tr!(lang, "maintenance-not-active"),
+                    Ok(Some(queue)) => {
+                        let mut applied = 0;
+                        for queued in &queue {
+                            if apply_queued_registration(ctx, state, queued.user_id, &queued.gamertag).await {
+                                applied += 1;
+                            }
+                        }
+                        audit(state, interaction, Ok(format!("maintenance off, {}/{} queued registrations applied", applied, queue.len())));
+                        tr!(lang, "maintenance-off", applied = applied, queued = queue.len())
+                    }
+                    Err(e) => {
+                        audit(state, interaction, Err(e.to_string()));
+                        eprintln!("Failed to end maintenance: {}", e);
+                        tr!(lang, "maintenance-failed", error = e.to_string())
+                    }
+                };
+                interaction
+                    .edit_response(&ctx.http, EditInteractionResponse::new().content(msg))
+                    .await?;
+            }
+        }
+        "update" => {
+            if !is_admin(state, interaction) {
+                return deny(ctx, interaction, state, lang).await;
+            }
+            if subcommand(interaction) == Some("check") {
+                interaction.defer_ephemeral(&ctx.http).await?;
+                let msg = match state.updater.check().await {
+                    Ok(check) => {
+                        let status_id = match &check.current {
+                            None => "update-current-unknown",
+                            Some(_) if check.is_update_available() => "update-available",
+                            Some(_) => "update-up-to-date",
+                        };
+                        format!(
+                            "{}\n{}",
+                            tr!(
+                                lang,
+                                "update-versions",
+                                current = check.current.unwrap_or_else(|| "-".to_string()),
+                                latest = check.latest.version,
+                            ),
+                            tr!(lang, status_id),
+                        )
+                    }
+                    Err(e) => {
+                        eprintln!("Update check failed: {}", e);
+                        tr!(lang, "update-check-failed", error = e.to_string())
+                    }
+                };
+                interaction
+                    .edit_response(&ctx.http, EditInteractionResponse::new().content(msg))
+                    .await?;
+            } else {
+                if !confirmed {
+                    return ask_confirmation(ctx, interaction, state, lang).await;
+                }
+                respond(ctx, interaction, tr!(lang, "update-in-progress"), true, confirmed).await?;
+                let source = string_option(interaction, "source")
+                    .filter(|source| !source.trim().is_empty())
+                    .map(UpdateSource::parse);
+                let result = state.updater.install(source).await;
+                audit(
+                    state,
+                    interaction,
+                    result
+                        .as_ref()
+                        .map(|result| format!("updated {} files to {}", result.files, result.version.as_deref().unwrap_or("unknown version")))
+                        .map_err(|e| e.to_string()),
+                );
+                let msg = match result {
+                    Ok(result) => tr!(
+                        lang,
+                        "update-done",
+                        version = result.version.unwrap_or_else(|| "?".to_string()),
+                        files = result.files,
+                        backup = result.backup.display().to_string(),
+                    ),
+                    Err(e) => {
+                        eprintln!("Update failed: {}", e);
+                        tr!(lang, "update-failed", error = e.to_string())
+                    }
+                };
+                interaction
+                    .edit_response(&ctx.http, EditInteractionResponse::new().content(msg))
+                    .await?;
+            }
+        }
+        "addon" => {
+            if !is_admin(state, interaction) {
+                return deny(ctx, interaction, state, lang).await;
+            }
+            match subcommand(interaction) {
+                Some("install") => {
+                    let Some(attachment) = attachment_option(interaction, "file") else {
+                        return Ok(());
+                    };
+                    if let Some(msg) = too_large(lang, attachment, addons::MAX_UPLOAD_SIZE) {
+                        return reply(ctx, interaction, msg, true).await;
+                    }
+                    interaction.defer_ephemeral(&ctx.http).await?;
+                    let result = match attachment.download().await {
+                        Ok(bytes) => {
+                            let addons = Arc::clone(&state.addons);
+                            let file_name = attachment.filename.clone();
+                            tokio::task::spawn_blocking(move || addons.install(&file_name, &bytes)).await?
+                        }
+                        Err(e) => Err(e.into()),
+                    };
+                    audit(
+                        state,
+                        interaction,
+                        result
+                            .as_ref()
+                            .map(|packs| {
+                                let names: Vec<String> = packs.iter().map(|pack| format!("{} ({})", pack.name, pack.uuid)).collect();
+                                format!("installed {}", names.join(", "))
+                            })
+                            .map_err(|e| e.to_string()),
+                    );
+                    let msg = match result {
+                        Ok(packs) => {
+                            let lines = packs.iter().map(|pack| pack_line(lang, pack)).collect();
+                            format!("{}\n{}", join_lines(tr!(lang, "addon-installed", count = packs.len()), lines), tr!(lang, "addon-restart-needed"))
+                        }
+                        Err(e) => {
+                            eprintln!("Failed to install add-on {}: {}", attachment.filename, e);
+                            tr!(lang, "addon-install-failed", error = e.to_string())
+                        }
+                    };
+                    interaction
+                        .edit_response(&ctx.http, EditInteractionResponse::new().content(msg))
+                        .await?;
+                }
+                Some("list") => {
+                    let msg = match state.addons.list() {
+                        Ok(packs) if packs.is_empty() => tr!(lang, "addon-list-empty"),
+                        Ok(packs) => {
+                            let header = tr!(lang, "addon-list-title", count = packs.len());
+                            join_lines(header, packs.iter().map(|pack| pack_line(lang, pack)).collect())
+                        }
+                        Err(e) => {
+                            eprintln!("Failed to list add-ons: {}", e);
+                            tr!(lang, "addon-failed", error = e.to_string())
+                        }
+                    };
+                    reply(ctx, interaction, msg, true).await?;
+                }
+                Some("remove") => {
+                    let query = string_option(interaction, "pack").unwrap_or_default().trim();
+                    if !confirmed {
+                        return ask_confirmation(ctx, interaction, state, lang).await;
+                    }
+                    let addons = Arc::clone(&state.addons);
+                    let pack = query.to_string();
+                    let result = tokio::task::spawn_blocking(move || addons.remove(&pack)).await?;
+                    audit(
+                        state,
+                        interaction,
+                        match &result {
+                            Ok(Some(pack)) => Ok(format!("removed {} ({})", pack.name, pack.uuid)),
+                            Ok(None) => Err("pack not found".to_string()),
+                            Err(e) => Err(e.to_string()),
+                        },
+                    );
+                    let msg = match result {
+                        Ok(Some(pack)) => format!("{}\n{}", tr!(lang, "addon-removed", pack = pack.name), tr!(lang, "addon-restart-needed")),
+                        Ok(None) => tr!(lang, "addon-not-found", pack = query),
+                        Err(e) => {
+                            eprintln!("Failed to remove add-on {}: {}", query, e);
+                            tr!(lang, "addon-failed", error = e.to_string())
+                        }
+                    };
+                    interaction
+                        .edit_response(&ctx.http, EditInteractionResponse::new().content(msg))
+                        .await?;
+                }
+                _ => {}
+            }
+        }
+        "world" => {
+            if !is_admin(state, interaction) {
+                return deny(ctx, interaction, state, lang).await;
+            }
+            match subcommand(interaction) {
+                Some("list") => {
+                    let msg = match state.worlds.list() {
+                        Ok(worlds) if worlds.is_empty() => tr!(lang, "world-list-empty"),
+                        Ok(worlds) => {
+                            let header = tr!(lang, "world-list-title", count = worlds.len());
+                            let lines = worlds
+                                .iter()
+                                .map(|world| {
+                                    let mut line = tr!(lang, "world-list-entry", name = world.name.as_str(), size = format_size(world.size));
+                                    if let Some(display_name) = &world.display_name {
+                                        line.push_str(&format!(" ({})", display_name));
+                                    }
+                                    if world.active {
+                                        line.push(' ');
+                                        line.push_str(&tr!(lang, "world-active"));
+                                    }
+                                    line
+                                })
+                                .collect();
+                            join_lines(header, lines)
+                        }
+                        Err(e) => {
+                            eprintln!("Failed to list worlds: {}", e);
+                            tr!(lang, "world-failed", error = e.to_string())
+                        }
+                    };
+                    reply(ctx, interaction, msg, true).await?;
+                }
+                Some("switch") => {
+                    let name = string_option(interaction, "name").unwrap_or_default().trim();
+                    if !confirmed {
+                        return ask_confirmation(ctx, interaction, state, lang).await;
+                    }
+                    respond(ctx, interaction, tr!(lang, "world-switching", name = name), false, confirmed).await?;
+                    let result = state.worlds.switch(name).await;
+                    audit(
+                        state,
+                        interaction,
+                        result.as_ref().map(|switch| format!("{} -> {}", switch.previous, name)).map_err(|e| e.to_string()),
+                    );
+                    let msg = match result {
+                        Ok(switch) if switch.restarted => tr!(lang, "world-switched", name = name, previous = switch.previous),
+                        Ok(switch) => tr!(lang, "world-switched-stopped", name = name, previous = switch.previous),
+                        Err(e) => {
+                            eprintln!("Failed to switch world to {}: {}", name, e);
+                            tr!(lang, "world-switch-failed", error = e.to_string())
+                        }
+                    };
+                    interaction
+                        .edit_response(&ctx.http, EditInteractionResponse::new().content(msg))
+                        .await?;
+                }
+                Some("upload") => {
+                    let Some(attachment) = attachment_option(interaction, "file") else {
+                        return Ok(());
+                    };
+                    if let Some(msg) = too_large(lang, attachment, worlds::MAX_UPLOAD_SIZE) {
+                        return reply(ctx, interaction, msg, true).await;
+                    }
+                    interaction.defer_ephemeral(&ctx.http).await?;
+                    let name = string_option(interaction, "name").map(str::trim).filter(|name| !name.is_empty()).map(str::to_string);
+                    let result = match attachment.download().await {
+                        Ok(bytes) => {
+                            let worlds = Arc::clone(&state.worlds);
+                            let file_name = attachment.filename.clone();
+                            tokio::task::spawn_blocking(move || worlds.upload(&file_name, &bytes, name.as_deref())).await?
+                        }
+                        Err(e) => Err(e.into()),
+                    };
+                    audit(state, interaction, result.as_ref().map(|world| format!("added {}", world.name)).map_err(|e| e.to_string()));
+                    let msg = match result {
+                        Ok(world) => tr!(lang, "world-uploaded", name = world.name, size = format_size(world.size)),
+                        Err(e) => {
+                            eprintln!("Failed to add world from {}: {}", attachment.filename, e);
+                            tr!(lang, "world-upload-failed", error = e.to_string())
+                        }
+                    };
+                    interaction
+                        .edit_response(&ctx.http, EditInteractionResponse::new().content(msg))
+                        .await?;
+                }
+                _ => {}
+            }
+        }
+        "config" => {
+            if !is_admin(state, interaction) {
+                return deny(ctx, interaction, state, lang).await;
+            }
+            let key = string_option(interaction, "key").map(str::trim);
+            let msg = match (subcommand(interaction), key) {
+                (Some("get"), key) => match state.config.load() {
+                    Ok(properties) => match key {
+                        Some(key) => match properties.get(key) {
+                            Some(value) => tr!(lang, "config-value", key = key, value = value),
+                            None => tr!(lang, "config-not-found", key = key),
+                        },
+                        None => {
+                            let lines = properties
+                                .entries()
+                                .map(|(key, value)| tr!(lang, "config-value", key = key, value = value))
+                                .collect();
+                            join_lines(tr!(lang, "config-title"), lines)
+                        }
+                    },
+                    Err(e) => {
+                        eprintln!("Failed to read server.properties: {}", e);
+                        tr!(lang, "config-failed", error = e.to_string())
+                    }
+                },
+                (Some("set"), Some(key)) => {
+                    let value = string_option(interaction, "value").unwrap_or_default();
+                    let result = state.config.set(key, value);
+                    audit(
+                        state,
+                        interaction,
+                        result
+                            .as_ref()
+                            .map(|change| format!("{:?} -> {}", change.previous, change.value))
+                            .map_err(|e| e.to_string()),
+                    );
+                    match result {
+                        Ok(change) => {
+                            let mut msg = tr!(
+                                lang,
+                                "config-updated",
+                                key = key,
+                                previous = change.previous.unwrap_or_else(|| "-".to_string()),
+                                value = change.value,
+                            );
+                            if change.needs_restart {
+                                msg.push('\n');
+                                msg.push_str(&tr!(lang, "config-restart-needed"));
+                            }
+                            msg
+                        }
+                        Err(e) => match e.downcast_ref::<InvalidValue>() {
+                            Some(invalid) => invalid_value_message(lang, key, invalid),
+                            None => tr!(lang, "config-failed", error = e.to_string()),
+                        },
+                    }
+                }
+                _ => return Ok(()),
+            };
+            reply(ctx, interaction, msg, true).await?;
+        }
+        "op" | "deop" => {
+            if !is_admin(state, interaction) {
+                return deny(ctx, interaction, state, lang).await;
+            }
+            let Some(gamertag) = target_gamertag(state, interaction) else {
+                return reply(ctx, interaction, tr!(lang, "op-specify-player"), true).await;
+            };
+            let Some(xuid) = state.permissions.resolve_xuid(&gamertag).await else {
+                return reply(ctx, interaction, tr!(lang, "op-xuid-unknown", gamertag = gamertag.as_str()), true).await;
+            };
+            let permission = if interaction.data.name == "op" {
+                Some(
+                    string_option(interaction, "level")
+                        .and_then(Permission::from_name)
+                        .unwrap_or(Permission::Operator),
+                )
+            } else {
+                None
+            };
+            let result = state.permissions.set(&gamertag, &xuid, permission).await;
+            audit(
+                state,
+                interaction,
+                result
+                    .as_ref()
+                    .map(|_| format!("{} ({}) -> {}", gamertag, xuid, permission.map_or("default", Permission::name)))
+                    .map_err(|e| e.to_string()),
+            );
+            let msg = match result {
+                Ok(applied) => {
+                    let mut msg = match permission {
+                        Some(permission) => tr!(
+                            lang,
+                            "op-updated",
+                            gamertag = gamertag.as_str(),
+                            level = tr!(lang, &format!("permission-{}", permission.name())),
+                        ),
+                        None => tr!(lang, "deop-done", gamertag = gamertag.as_str()),
+                    };
+                    if !applied {
+                        msg.push('\n');
+                        msg.push_str(&tr!(lang, "op-next-start"));
+                    }
+                    msg
+                }
+                Err(e) => {
+                    eprintln!("Failed to update permissions for {}: {}", gamertag, e);
+                    tr!(lang, "op-failed", error = e.to_string())
+                }
+            };
+            reply(ctx, interaction, msg, true).await?;
+        }
+        "ops" => {
+            if !is_admin(state, interaction) {
+                return deny(ctx, interaction, state, lang).await;
+            }
+            let msg = match state.permissions.list().await {
+                Ok(entries) if entries.is_empty() => tr!(lang, "ops-empty"),
+                Ok(entries) => {
+                    let lines = entries
+                        .iter()
+                        .map(|entry| tr!(
+                            lang,
+                            "ops-entry",
+                            name = entry.name.clone().unwrap_or_else(|| "?".to_string()),
+                            xuid = entry.xuid.as_str(),
+                            level = tr!(lang, &format!("permission-{}", entry.permission.name())),
+                        ))
+                        .collect();
+                    join_lines(tr!(lang, "ops-title", count = entries.len()), lines)
+                }
+                Err(e) => {
+                    eprintln!("Failed to read permissions: {}", e);
+                    tr!(lang, "op-failed", error = e.to_string())
+                }
+            };
+            reply(ctx, interaction, msg, true).await?;
+        }
+        "kick" | "ban" | "unban" => {
+            if !is_admin(state, interaction) {
+                return deny(ctx, interaction, state, lang).await;
+            }
+            let Some(gamertag) = target_gamertag(state, interaction) else {
+                return reply(ctx, interaction, tr!(lang, "op-specify-player"), true).await;
+            };
+            let reason = string_option(interaction, "reason").map(str::trim).filter(|reason| !reason.is_empty());
+            let msg = match interaction.data.name.as_str() {
+                "kick" => {
+                    let result = state.bans.kick(&gamertag, reason);
+                    audit(state, interaction, result.as_ref().map(|_| format!("kicked {}", gamertag)).map_err(|e| e.to_string()));
+                    match result {
+                        Ok(()) => tr!(lang, "kick-done", gamertag = gamertag.as_str()),
+                        Err(e) => tr!(lang, "moderation-failed", error = e.to_string()),
+                    }
+                }
+                "ban" => {
+                    let duration = match string_option(interaction, "duration") {
+                        Some(text) => match parse_duration(text) {
+                            Ok(duration) => Some(duration),
+                            Err(_) => {
+                                return reply(ctx, interaction, tr!(lang, "guest-invalid-duration", duration = text), true).await;
+                            }
+                        },
+                        None => None,
+                    };
+                    if !confirmed {
+                        return ask_confirmation(ctx, interaction, state, lang).await;
+                    }
+                    let user_id = user_option(interaction, "user")
+                        .or_else(|| state.links.user_for(&gamertag).map(|(user_id, _)| user_id));
+                    let result = state.bans.ban(&gamertag, user_id, duration, reason, interaction.user.id);
+                    audit(
+                        state,
+                        interaction,
+                        result
+                            .as_ref()
+                            .map(|ban| match ban.expires_at {
+                                Some(expires_at) => format!("banned {} until {}", gamertag, expires_at.to_rfc3339()),
+                                None => format!("banned {} permanently", gamertag),
+                            })
+                            .map_err(|e| e.to_string()),
+                    );
+                    match result {
+                        Ok(ban) => tr!(lang, "ban-done", gamertag = gamertag.as_str(), until = ban_expiry(lang, &ban)),
+                        Err(e) => {
+                            eprintln!("Failed to ban {}: {}", gamertag, e);
+                            tr!(lang, "moderation-failed", error = e.to_string())
+                        }
+                    }
+                }
+                _ => {
+                    let result = state.bans.unban(&gamertag);
+                    audit(
+                        state,
+                        interaction,
+                        result
+                            .as_ref()
+                            .map(|removed| if *removed { format!("unbanned {}", gamertag) } else { "not banned".to_string() })
+                            .map_err(|e| e.to_string()),
+                    );
+                    match result {
+                        Ok(true) => tr!(lang, "unban-done", gamertag = gamertag.as_str()),
+                        Ok(false) => tr!(lang, "unban-not-found", gamertag = gamertag.as_str()),
+                        Err(e) => tr!(lang, "moderation-failed", error = e.to_string()),
+                    }
+                }
+            };
+            respond(ctx, interaction, msg, true, confirmed).await?;
+        }
+        "audit" => {
+            if !is_admin(state, interaction) {
+                return deny(ctx, interaction, state, lang).await;
+            }
+            if subcommand(interaction) == Some("search") {
+                let query = AuditQuery {
+                    actor: user_option(interaction, "user"),
+                    command: string_option(interaction, "command").map(str::to_string),
+                    text: string_option(interaction, "text").map(str::to_string),
+                };
+                let limit = integer_option(interaction, "limit").unwrap_or(AUDIT_SEARCH_LIMIT as i64).clamp(1, 50) as usize;
+                let msg = match state.audit.search(&query, limit) {
+                    Ok(entries) if entries.is_empty() => tr!(lang, "audit-search-empty"),
+                    Ok(entries) => {
+                        let lines = entries
+                            .iter()
+                            .map(|entry| tr!(
+                                lang,
+                                "audit-search-entry",
+                                time = format!("<t:{}:f>", entry.timestamp.timestamp()),
+                                icon = if entry.success { "✅" } else { "❌" },
+                                actor = entry.actor.map_or_else(|| tr!(lang, "audit-actor-bot"), |actor| format!("<@{}>", actor)),
+                                command = entry.command.as_str(),
+                                arguments = entry.format_arguments(),
+                                result = entry.result.as_str(),
+                            ))
+                            .collect();
+                        join_lines(tr!(lang, "audit-search-title", count = entries.len()), lines)
+                    }
+                    Err(e) => {
+                        eprintln!("Failed to search the audit log: {}", e);
+                        tr!(lang, "audit-search-failed", error = e.to_string())
+                    }
+                };
+                reply(ctx, interaction, msg, true).await?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// allowlist に追加し、Discord ユーザーと紐づける
+fn add_registration(state: &AppState, user_id: UserId, gamertag: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let result = state.server_controller.send_command(&format!("allowlist add \"{}\"", gamertag));
+    state.audit.record(AuditEntry::new(
+        Some(user_id),
+        "server",
+        BTreeMap::from([("gamertag".to_string(), gamertag.to_string())]),
+        result.as_ref().map(|_| "allowlist add".to_string()).map_err(|e| e.to_string()),
+    ));
+    result?;
+    if let Err(e) = state.links.link(user_id, gamertag) {
+        eprintln!("Failed to save link for {}: {}", gamertag, e);
+    }
+    Ok(())
+}
+
+/// メンテナンス中に受け付けた登録を反映し、結果をDMで知らせる
+async fn apply_queued_registration(ctx: &Context, state: &AppState, user_id: UserId, gamertag: &str) -> bool {
+    let lang = Language::from_env();
+    let (applied, msg) = if let Some(ban) = state.bans.find(gamertag, Some(user_id)) {
+        (false, tr!(lang, "server-banned", until = ban_expiry(lang, &ban)))
+    } else if let Err(e) = add_registration(state, user_id, gamertag) {
+        eprintln!("Failed to apply queued registration for {}: {}", gamertag, e);
+        (false, tr!(lang, "server-command-failed"))
+    } else {
+        (true, tr!(lang, "server-added", gamertag = gamertag))
+    };
+    if let Err(e) = user_id.direct_message(&ctx.http, CreateMessage::new().content(msg)).await {
+        eprintln!("Failed to notify {} about their registration: {}", user_id, e);
+    }
+    applied
+}
+
+/// 確認メッセージのボタン。custom_id は `confirm:<id>` か `cancel:<id>`
+pub async fn handle_component(
+    ctx: &Context,
+    interaction: &ComponentInteraction,
+    state: &AppState,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let lang = Language::for_locale(&interaction.locale);
+    let Some((action, id)) = interaction.data.custom_id.split_once(':') else {
+        return Ok(());
+    };
+    let update = |content: String| {
+        CreateInteractionResponse::UpdateMessage(
+            CreateInteractionResponseMessage::new().content(content).components(vec![])
+        )
+    };
+    let command = match state.confirmations.take(id, interaction.user.id) {
+        Ok(command) => command,
+        Err(ConfirmError::WrongUser) => {
             interaction
                 .create_response(
-                    &ctx.http, 
+                    &ctx.http,
                     CreateInteractionResponse::Message(
-                        CreateInteractionResponseMessage::new().content(msg).ephemeral(false)
+                        CreateInteractionResponseMessage::new().content(tr!(lang, "confirm-not-yours")).ephemeral(true)
                     )
                 )
                 .await?;
-            let controller = Arc::clone(&server_controller);
-            tokio::task::spawn_blocking(move || {
-                if let Err(e) = controller.restart() {
-                    eprintln!("Failed to restart server: {}", e);
-                }
-            });
+            return Ok(());
         }
-        _ => {}
+        Err(ConfirmError::NotFound) => {
+            interaction.create_response(&ctx.http, update(tr!(lang, "confirm-expired"))).await?;
+            return Ok(());
+        }
+    };
+    if action != "confirm" {
+        interaction.create_response(&ctx.http, update(tr!(lang, "confirm-cancelled"))).await?;
+        return Ok(());
     }
-    Ok(())
+    interaction.create_response(&ctx.http, update(tr!(lang, "confirm-running"))).await?;
+    run_command(ctx, &command, state, true).await
 }
 
 pub async fn handle_modal(
     ctx: &Context, 
     interaction: &ModalInteraction, 
-    server_controller: Arc<ServerController>
+    state: &AppState
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let lang = env::var("LANGUAGE").unwrap_or_else(|_| "JP".to_string());
-    let is_en = lang.to_uppercase() == "EN";
+    let lang = Language::for_locale(&interaction.locale);
     let game_id = interaction
         .data
         .components
@@ -84,10 +1413,19 @@ pub async fn handle_modal(
             }
         })
         .unwrap_or_default();
+    // 全体とユーザーごとの制限は /server を実行したときに数えている
+    if let Err(wait) = state.rate_limiter.check_command(interaction.user.id, "server") {
+        let response = CreateInteractionResponseMessage::new()
+            .content(rate_limited_message(lang, wait))
+            .ephemeral(true);
+        interaction
+            .create_response(&ctx.http, CreateInteractionResponse::Message(response))
+            .await?;
+        return Ok(());
+    }
     if game_id.is_empty() {
-        let msg = if is_en { "❌ Please enter a Game ID." } else { "❌ ゲームIDを入力してください。" };
         let response = CreateInteractionResponseMessage::new()
-            .content(msg)
+            .content(tr!(lang, "server-empty-game-id"))
             .ephemeral(true);
         
         interaction
@@ -95,37 +1433,61 @@ pub async fn handle_modal(
             .await?;
         return Ok(());
     }
-    match server_controller.send_command(&format!("allowlist add \"{}\"", game_id)) {
-        Ok(_) => {
-             let msg = if is_en {
-                format!("✅ `{}` has been added to the whitelist!", game_id)
-            } else {
-                format!("✅ `{}` をホワイトリストに追加しました！", game_id)
-            };
-            let response = CreateInteractionResponseMessage::new()
-                .content(msg)
-                .ephemeral(true);
-            
-            interaction
-                .create_response(&ctx.http, CreateInteractionResponse::Message(response))
-                .await?;
-        }
+    let arguments = BTreeMap::from([("gamertag".to_string(), game_id.clone())]);
+    if let Some(ban) = state.bans.find(&game_id, Some(interaction.user.id)) {
+        state.audit.record(AuditEntry::new(
+            Some(interaction.user.id),
+            "server",
+            arguments,
+            Err(format!("refused: {} is banned", ban.gamertag)),
+        ));
+        let response = CreateInteractionResponseMessage::new()
+            .content(tr!(lang, "server-banned", until = ban_expiry(lang, &ban)))
+            .ephemeral(true);
+        interaction
+            .create_response(&ctx.http, CreateInteractionResponse::Message(response))
+            .await?;
+        return Ok(());
+    }
+    if let Some(info) = state.maintenance.current() {
+        let result = state.maintenance.enqueue(&game_id, interaction.user.id);
+        state.audit.record(AuditEntry::new(
+            Some(interaction.user.id),
+            "server",
+            arguments,
+            result.as_ref().map(|_| "queued until maintenance ends".to_string()).map_err(|e| e.to_string()),
+        ));
+        let msg = match result {
+            Ok(()) => tr!(
+                lang,
+                "server-queued",
+                gamertag = game_id.as_str(),
+                eta = info.eta.map(|eta| format!("<t:{}:R>", eta.timestamp())).unwrap_or_else(|| "-".to_string()),
+            ),
+            Err(e) => {
+                eprintln!("Failed to queue registration for {}: {}", game_id, e);
+                tr!(lang, "server-command-failed")
+            }
+        };
+        let response = CreateInteractionResponseMessage::new().content(msg).ephemeral(true);
+        interaction
+            .create_response(&ctx.http, CreateInteractionResponse::Message(response))
+            .await?;
+        return Ok(());
+    }
+    let msg = match add_registration(state, interaction.user.id, &game_id) {
+        Ok(()) => tr!(lang, "server-added", gamertag = game_id.as_str()),
         Err(e) => {
             eprintln!("Error sending command: {}", e);
-             let msg = if is_en {
-                "❌ Failed to send command to server."
-            } else {
-                "❌ サーバーへのコマンド送信に失敗しました。"
-            };
-            let response = CreateInteractionResponseMessage::new()
-                .content(msg)
-                .ephemeral(true);
-            
-            interaction
-                .create_response(&ctx.http, CreateInteractionResponse::Message(response))
-                .await?;
+            tr!(lang, "server-command-failed")
         }
-    }
+    };
+    let response = CreateInteractionResponseMessage::new()
+        .content(msg)
+        .ephemeral(true);
+
+    interaction
+        .create_response(&ctx.http, CreateInteractionResponse::Message(response))
+        .await?;
     Ok(())
 }
-
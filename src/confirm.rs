@@ -0,0 +1,46 @@
+use serenity::all::{CommandInteraction, UserId};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 確認ボタンの有効期限
+pub const CONFIRM_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfirmError {
+    /// 期限切れか、すでに押された
+    NotFound,
+    /// コマンドを実行した本人ではない
+    WrongUser,
+}
+
+/// 確認待ちのコマンド。ボタンの custom_id にはコマンドのインタラクションIDを使う
+#[derive(Default)]
+pub struct Confirmations {
+    pending: Mutex<HashMap<String, (CommandInteraction, Instant)>>,
+}
+
+impl Confirmations {
+    /// 確認待ちとして保存し、ボタンに付けるIDを返す
+    pub fn request(&self, interaction: &CommandInteraction) -> String {
+        let id = interaction.id.to_string();
+        let mut pending = self.pending.lock().unwrap();
+        pending.retain(|_, (_, requested)| requested.elapsed() < CONFIRM_TIMEOUT);
+        pending.insert(id.clone(), (interaction.clone(), Instant::now()));
+        id
+    }
+
+    /// ボタンを押したユーザーが本人なら、確認待ちのコマンドを取り出す
+    pub fn take(&self, id: &str, user_id: UserId) -> Result<CommandInteraction, ConfirmError> {
+        let mut pending = self.pending.lock().unwrap();
+        match pending.get(id) {
+            None => return Err(ConfirmError::NotFound),
+            Some((interaction, _)) if interaction.user.id != user_id => return Err(ConfirmError::WrongUser),
+            Some(_) => {}
+        }
+        match pending.remove(id) {
+            Some((interaction, requested)) if requested.elapsed() < CONFIRM_TIMEOUT => Ok(interaction),
+            _ => Err(ConfirmError::NotFound),
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use crate::server_controller::ServerController;
+use regex::{Captures, Regex};
+use serenity::all::{ChannelId, CreateMessage, Http};
+use std::env;
+use std::sync::{Arc, OnceLock};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::time::{sleep_until, Duration, Instant};
+
+/// Discordのメッセージ上限 (2000文字) からコードブロックの分を引いた長さ
+const MAX_BATCH_LEN: usize = 1900;
+const BATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// bedrock_server のコンソール出力を Discord のチャンネルに転送する
+pub struct ConsoleMirror {
+    channel_id: ChannelId,
+    filter: Option<Regex>,
+    output: broadcast::Receiver<String>,
+}
+
+impl ConsoleMirror {
+    /// CONSOLE_CHANNEL_ID が未設定なら None。CONSOLE_FILTER に一致する行は転送しない
+    ///
+    /// 起動直後の出力も転送できるよう、サーバーの起動前に呼ぶこと
+    pub fn from_env(server_controller: &ServerController) -> Result<Option<Self>, Box<dyn std::error::Error + Send + Sync>> {
+        let Ok(channel_id) = env::var("CONSOLE_CHANNEL_ID") else {
+            return Ok(None);
+        };
+        let channel_id = channel_id
+            .parse::<u64>()
+            .map_err(|_| "CONSOLE_CHANNEL_ID must be a valid u64")?;
+        let filter = match env::var("CONSOLE_FILTER") {
+            Ok(pattern) if !pattern.is_empty() => {
+                Some(Regex::new(&pattern).map_err(|e| format!("CONSOLE_FILTER: {}", e))?)
+            }
+            _ => None,
+        };
+        Ok(Some(Self {
+            channel_id: ChannelId::new(channel_id),
+            filter,
+            output: server_controller.subscribe_output(),
+        }))
+    }
+
+    pub fn start(self, http: Arc<Http>) {
+        tokio::spawn(async move {
+            self.mirror_loop(http).await;
+        });
+    }
+
+    async fn mirror_loop(mut self, http: Arc<Http>) {
+        let mut batch = String::new();
+        let mut deadline: Option<Instant> = None;
+
+        loop {
+            let line = match deadline {
+                Some(at) => tokio::select! {
+                    line = self.output.recv() => Some(line),
+                    _ = sleep_until(at) => None,
+                },
+                None => Some(self.output.recv().await),
+            };
+
+            match line {
+                Some(Ok(line)) => {
+                    if self.filter.as_ref().is_some_and(|filter| filter.is_match(&line)) {
+                        continue;
+                    }
+                    let line = sanitize_line(&line);
+                    if batch.len() + line.len() + 1 > MAX_BATCH_LEN {
+                        self.flush(&http, &mut batch).await;
+                    }
+                    batch.push_str(&line);
+                    batch.push('\n');
+                    deadline.get_or_insert_with(|| Instant::now() + BATCH_INTERVAL);
+                }
+                Some(Err(RecvError::Lagged(skipped))) => {
+                    batch.push_str(&format!("... {} lines skipped ...\n", skipped));
+                }
+                Some(Err(RecvError::Closed)) => break,
+                None => {
+                    self.flush(&http, &mut batch).await;
+                    deadline = None;
+                }
+            }
+        }
+    }
+
+    async fn flush(&self, http: &Http, batch: &mut String) {
+        if batch.is_empty() {
+            return;
+        }
+        let content = format!("```\n{}```", batch);
+        batch.clear();
+        if let Err(e) = self.channel_id.send_message(http, CreateMessage::new().content(content)).await {
+            eprintln!("Failed to mirror console output: {}", e);
+        }
+    }
+}
+
+/// IPアドレスを伏せ、コードブロックを壊さないようにし、長すぎる行を切り詰める。
+/// `1.21.50.07` のようなバージョン番号を伏せないよう、IPv4 は 0-255 の4つの数で前後に `.数字` が続かないものだけ
+pub fn sanitize_line(line: &str) -> String {
+    static IP_PATTERN: OnceLock<Regex> = OnceLock::new();
+    let ip_pattern = IP_PATTERN.get_or_init(|| {
+        Regex::new(concat!(
+            r"(?P<v4>\b(?:(?:25[0-5]|2[0-4]\d|1\d\d|[1-9]?\d)\.){3}(?:25[0-5]|2[0-4]\d|1\d\d|[1-9]?\d)\b)",
+            r"|(?i:\b(?:[0-9a-f]{1,4}:){7}[0-9a-f]{1,4}\b)",
+            // `std::abs` のような文字列を伏せないよう、`::` の前は単語の途中でないこと
+            r"|(?i:\b(?:[0-9a-f]{1,4}:)+:(?:[0-9a-f]{1,4}(?::[0-9a-f]{1,4})*\b)?|\B::[0-9a-f]{1,4}(?::[0-9a-f]{1,4})*\b)",
+        ))
+        .expect("invalid IP address pattern")
+    });
+    let line = ip_pattern
+        .replace_all(line, |captures: &Captures| {
+            let found = captures.get(0).unwrap();
+            let before = line[..found.start()].strip_suffix('.').is_some_and(|rest| rest.ends_with(|c: char| c.is_ascii_digit()));
+            let after = line[found.end()..].strip_prefix('.').is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_digit()));
+            if captures.name("v4").is_some() && (before || after) {
+                found.as_str().to_string()
+            } else {
+                "[redacted]".to_string()
+            }
+        })
+        .replace("```", "`\u{200b}``");
+    if line.len() < MAX_BATCH_LEN {
+        return line;
+    }
+    let mut end = MAX_BATCH_LEN - 4;
+    while !line.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}...", &line[..end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::sanitize_line;
+
+    #[test]
+    fn keeps_version_numbers() {
+        assert_eq!(sanitize_line("Version: 1.21.50.07"), "Version: 1.21.50.07");
+        assert_eq!(sanitize_line("Version 1.20.81.1.2"), "Version 1.20.81.1.2");
+        assert_eq!(sanitize_line("999.1.1.1"), "999.1.1.1");
+    }
+
+    #[test]
+    fn redacts_ipv4_addresses() {
+        assert_eq!(sanitize_line("Player connected from 192.168.0.1:19132"), "Player connected from [redacted]:19132");
+        assert_eq!(sanitize_line("10.0.0.255."), "[redacted].");
+    }
+
+    #[test]
+    fn redacts_ipv6_addresses() {
+        assert_eq!(sanitize_line("IPv6 2001:db8:85a3:0:0:8a2e:370:7334 port"), "IPv6 [redacted] port");
+        assert_eq!(sanitize_line("listening on fe80::1"), "listening on [redacted]");
+        assert_eq!(sanitize_line("bound to ::1"), "bound to [redacted]");
+    }
+
+    #[test]
+    fn keeps_scoped_names() {
+        assert_eq!(sanitize_line("std::abs and Foo::bar"), "std::abs and Foo::bar");
+        assert_eq!(sanitize_line("Vec::new()"), "Vec::new()");
+    }
+
+    #[test]
+    fn escapes_code_fences() {
+        assert_eq!(sanitize_line("```rust"), "`\u{200b}``rust");
+    }
+}
@@ -0,0 +1,62 @@
+use std::time::Duration;
+
+/// 読み取れる期間の上限 (約10年)。期限の計算であふれないようにする
+pub const MAX_DURATION: Duration = Duration::from_secs(10 * 365 * 86_400);
+
+/// `1d 2h 3m` 形式で表示する (1分未満は秒)
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let (days, hours, minutes) = (secs / 86_400, secs % 86_400 / 3_600, secs % 3_600 / 60);
+    if days > 0 {
+        format!("{}d {}h {}m", days, hours, minutes)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m", minutes)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+/// `30m` `48h` `2d` `1w` や `1d12h` のような期間を読み取る (MAX_DURATION まで)
+pub fn parse_duration(text: &str) -> Result<Duration, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err("empty duration".to_string());
+    }
+    let mut total = 0u64;
+    let mut number = String::new();
+    for c in text.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let unit = match c.to_ascii_lowercase() {
+            's' => 1,
+            'm' => 60,
+            'h' => 3_600,
+            'd' => 86_400,
+            'w' => 604_800,
+            c if c.is_whitespace() => continue,
+            _ => return Err(format!("unknown unit '{}' in '{}'", c, text)),
+        };
+        let value: u64 = number
+            .parse()
+            .map_err(|_| format!("missing number before '{}' in '{}'", c, text))?;
+        total = value
+            .checked_mul(unit)
+            .and_then(|secs| total.checked_add(secs))
+            .ok_or_else(|| format!("duration too long: '{}'", text))?;
+        number.clear();
+    }
+    if !number.is_empty() {
+        return Err(format!("missing unit after '{}' in '{}'", number, text));
+    }
+    if total == 0 {
+        return Err(format!("duration must be positive: '{}'", text));
+    }
+    if total > MAX_DURATION.as_secs() {
+        return Err(format!("duration too long: '{}'", text));
+    }
+    Ok(Duration::from_secs(total))
+}
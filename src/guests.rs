@@ -0,0 +1,126 @@
+use crate::allowlist::Allowlist;
+use crate::audit::{AuditEntry, AuditLog};
+use crate::i18n::{tr, Language};
+use crate::players::PlayerTracker;
+use crate::server_controller::ServerController;
+use crate::store::{load_json, save_json};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serenity::all::UserId;
+use std::collections::BTreeMap;
+use std::env;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GuestPass {
+    pub gamertag: String,
+    pub expires_at: DateTime<Utc>,
+    pub added_by: UserId,
+}
+
+/// 期限付きで allowlist に追加したゲスト
+pub struct GuestPasses {
+    path: PathBuf,
+    guests: Mutex<Vec<GuestPass>>,
+    allowlist: Allowlist,
+    audit: Arc<AuditLog>,
+    server_controller: Arc<ServerController>,
+    players: Arc<PlayerTracker>,
+}
+
+impl GuestPasses {
+    /// GUESTS_PATH (既定は guests.json) に保存し、期限切れの削除は監査ログに記録する
+    pub fn from_env(
+        server_controller: Arc<ServerController>,
+        players: Arc<PlayerTracker>,
+        audit: Arc<AuditLog>,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let path = PathBuf::from(env::var("GUESTS_PATH").unwrap_or_else(|_| "guests.json".to_string()));
+        let guests = load_json(&path)?;
+        Ok(Self {
+            path,
+            guests: Mutex::new(guests),
+            allowlist: Allowlist::from_env(&server_controller),
+            audit,
+            server_controller,
+            players,
+        })
+    }
+
+    /// allowlist に追加し、期限を記録する。同じゲーマータグのパスは上書きする
+    pub fn add(
+        &self,
+        gamertag: &str,
+        duration: Duration,
+        added_by: UserId,
+    ) -> Result<DateTime<Utc>, Box<dyn std::error::Error + Send + Sync>> {
+        let expires_at = Utc::now()
+            .checked_add_signed(chrono::Duration::from_std(duration)?)
+            .ok_or("guest pass duration is too long")?;
+        self.server_controller.send_command(&format!("allowlist add \"{}\"", gamertag))?;
+        let mut guests = self.guests.lock().unwrap();
+        guests.retain(|guest| !guest.gamertag.eq_ignore_ascii_case(gamertag));
+        guests.push(GuestPass { gamertag: gamertag.to_string(), expires_at, added_by });
+        save_json(&self.path, &*guests)?;
+        Ok(expires_at)
+    }
+
+    pub fn start(self: &Arc<Self>) {
+        let passes = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(CHECK_INTERVAL);
+            loop {
+                interval.tick().await;
+                passes.remove_expired().await;
+            }
+        });
+    }
+
+    async fn remove_expired(&self) {
+        let now = Utc::now();
+        let expired: Vec<GuestPass> = self
+            .guests
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|guest| guest.expires_at <= now)
+            .cloned()
+            .collect();
+        if expired.is_empty() {
+            return;
+        }
+        let lang = Language::from_env();
+        for guest in &expired {
+            if self.players.is_online(&guest.gamertag).await {
+                if let Err(e) = self.server_controller.kick(&guest.gamertag, Some(&tr!(lang, "guest-kick-reason"))) {
+                    eprintln!("Failed to kick expired guest {}: {}", guest.gamertag, e);
+                }
+            }
+        }
+        let names: Vec<String> = expired.iter().map(|guest| guest.gamertag.clone()).collect();
+        if let Err(e) = self.allowlist.remove(&names, &self.server_controller) {
+            // 次回また試す
+            eprintln!("Failed to remove expired guests: {}", e);
+            return;
+        }
+        {
+            let mut guests = self.guests.lock().unwrap();
+            guests.retain(|guest| guest.expires_at > now);
+            if let Err(e) = save_json(&self.path, &*guests) {
+                eprintln!("Failed to save guest passes: {}", e);
+            }
+        }
+        for guest in expired {
+            println!("Guest pass expired: {}", guest.gamertag);
+            let arguments = BTreeMap::from([
+                ("gamertag".to_string(), guest.gamertag),
+                ("added_by".to_string(), format!("<@{}>", guest.added_by)),
+            ]);
+            self.audit.record(AuditEntry::new(None, "guest expire", arguments, Ok("removed from the allowlist".to_string())));
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource};
+use std::collections::HashMap;
+use std::env;
+use std::sync::OnceLock;
+use unic_langid::LanguageIdentifier;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Language {
+    Japanese,
+    English,
+    Korean,
+}
+
+impl Language {
+    pub const ALL: [Language; 3] = [Language::Japanese, Language::English, Language::Korean];
+
+    fn code(self) -> &'static str {
+        match self {
+            Language::Japanese => "ja",
+            Language::English => "en",
+            Language::Korean => "ko",
+        }
+    }
+
+    fn source(self) -> &'static str {
+        match self {
+            Language::Japanese => include_str!("../locales/ja.ftl"),
+            Language::English => include_str!("../locales/en.ftl"),
+            Language::Korean => include_str!("../locales/ko.ftl"),
+        }
+    }
+
+    /// Discordのロケール名 (description_localized 用)
+    pub fn discord_locales(self) -> &'static [&'static str] {
+        match self {
+            Language::Japanese => &["ja"],
+            Language::English => &["en-US", "en-GB"],
+            Language::Korean => &["ko"],
+        }
+    }
+
+    /// 環境変数 LANGUAGE (JP / EN / KO) から既定の言語を決める
+    pub fn from_env() -> Self {
+        let lang = env::var("LANGUAGE").unwrap_or_else(|_| "JP".to_string());
+        match lang.to_uppercase().as_str() {
+            "EN" => Language::English,
+            "KO" | "KR" => Language::Korean,
+            _ => Language::Japanese,
+        }
+    }
+
+    /// Discordのロケール ("ja", "en-US", "ko" など) に対応する言語
+    pub fn from_discord_locale(locale: &str) -> Option<Self> {
+        let primary = locale.split('-').next().unwrap_or_default();
+        Language::ALL.into_iter().find(|lang| lang.code() == primary)
+    }
+
+    /// 操作したユーザーのロケールを優先し、未対応なら既定の言語を使う
+    pub fn for_locale(locale: &str) -> Self {
+        Self::from_discord_locale(locale).unwrap_or_else(Self::from_env)
+    }
+}
+
+type Bundle = FluentBundle<FluentResource>;
+
+fn bundles() -> &'static HashMap<Language, Bundle> {
+    static BUNDLES: OnceLock<HashMap<Language, Bundle>> = OnceLock::new();
+    BUNDLES.get_or_init(|| {
+        Language::ALL
+            .into_iter()
+            .map(|lang| {
+                let langid: LanguageIdentifier = lang.code().parse().expect("invalid language identifier");
+                let resource = FluentResource::try_new(lang.source().to_string())
+                    .unwrap_or_else(|(_, errors)| panic!("invalid {}.ftl: {:?}", lang.code(), errors));
+                let mut bundle = Bundle::new_concurrent(vec![langid]);
+                bundle.set_use_isolating(false);
+                bundle
+                    .add_resource(resource)
+                    .unwrap_or_else(|errors| panic!("duplicate messages in {}.ftl: {:?}", lang.code(), errors));
+                (lang, bundle)
+            })
+            .collect()
+    })
+}
+
+/// メッセージIDを翻訳する。見つからない場合は英語、それもなければIDをそのまま返す
+pub fn format(lang: Language, id: &str, args: Option<&FluentArgs>) -> String {
+    let bundles = bundles();
+    for candidate in [lang, Language::English] {
+        let bundle = &bundles[&candidate];
+        if let Some(pattern) = bundle.get_message(id).and_then(|msg| msg.value()) {
+            let mut errors = vec![];
+            let text = bundle.format_pattern(pattern, args, &mut errors);
+            if !errors.is_empty() {
+                eprintln!("Errors formatting message {}: {:?}", id, errors);
+            }
+            return text.into_owned();
+        }
+    }
+    eprintln!("Missing translation: {}", id);
+    id.to_string()
+}
+
+/// `tr!(lang, "message-id")` / `tr!(lang, "message-id", name = value, ...)`
+macro_rules! tr {
+    ($lang:expr, $id:expr) => {
+        $crate::i18n::format($lang, $id, None)
+    };
+    ($lang:expr, $id:expr, $($key:ident = $value:expr),+ $(,)?) => {{
+        let mut args = fluent_bundle::FluentArgs::new();
+        $( args.set(stringify!($key), $value); )+
+        $crate::i18n::format($lang, $id, Some(&args))
+    }};
+}
+pub(crate) use tr;
@@ -0,0 +1,226 @@
+use crate::backup::BackupManager;
+use crate::server_controller::{ServerController, ServerState};
+use std::env;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::process::Command;
+
+/// スクリプトの実行を待つ最大時間
+const SCRIPT_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+/// post-start のフックを実行する前に起動完了を待つ最大時間
+const READY_TIMEOUT: Duration = Duration::from_secs(120);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookEvent {
+    PreStop,
+    PostStart,
+    PreRestart,
+}
+
+impl HookEvent {
+    fn name(self) -> &'static str {
+        match self {
+            HookEvent::PreStop => "pre-stop",
+            HookEvent::PostStart => "post-start",
+            HookEvent::PreRestart => "pre-restart",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum HookAction {
+    /// ワールドをバックアップする
+    Backup,
+    /// ゲーム内に `say` で告知する
+    Announce(String),
+    /// シェルのコマンドを実行する (環境変数 HOOK_EVENT にイベント名が入る)
+    Script(String),
+}
+
+#[derive(Debug, Clone)]
+struct Hook {
+    action: HookAction,
+    /// 失敗したら停止・再起動を中止する
+    required: bool,
+}
+
+impl Hook {
+    /// `backup` / `announce:メッセージ` / `script:コマンド`。先頭に `!` を付けると必須
+    fn parse(spec: &str) -> Result<Self, String> {
+        let spec = spec.trim();
+        let (required, spec) = match spec.strip_prefix('!') {
+            Some(rest) => (true, rest.trim_start()),
+            None => (false, spec),
+        };
+        let (kind, arg) = spec.split_once(':').map_or((spec, ""), |(kind, arg)| (kind, arg.trim()));
+        let action = match kind.trim().to_lowercase().as_str() {
+            "backup" => HookAction::Backup,
+            "announce" if !arg.is_empty() => HookAction::Announce(arg.to_string()),
+            "script" if !arg.is_empty() => HookAction::Script(arg.to_string()),
+            _ => return Err(format!("invalid hook '{}'", spec)),
+        };
+        Ok(Self { action, required })
+    }
+
+    fn describe(&self) -> String {
+        match &self.action {
+            HookAction::Backup => "backup".to_string(),
+            HookAction::Announce(_) => "announce".to_string(),
+            HookAction::Script(command) => format!("script '{}'", command),
+        }
+    }
+}
+
+/// 復元したバックアップ
+#[derive(Debug, Clone)]
+pub struct Restored {
+    pub worlds: Vec<String>,
+    /// サーバーを起動し直した
+    pub restarted: bool,
+}
+
+/// フックを挟んでサーバーを起動・停止・再起動する
+pub struct Lifecycle {
+    server_controller: Arc<ServerController>,
+    backups: Arc<BackupManager>,
+    pre_stop: Vec<Hook>,
+    post_start: Vec<Hook>,
+    pre_restart: Vec<Hook>,
+}
+
+impl Lifecycle {
+    /// HOOKS_PRE_STOP / HOOKS_POST_START / HOOKS_PRE_RESTART に `;` 区切りでフックを指定する
+    /// (例: "!backup;announce:まもなく再起動します")
+    pub fn from_env(
+        server_controller: Arc<ServerController>,
+        backups: Arc<BackupManager>,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let read = |var: &str| -> Result<Vec<Hook>, String> {
+            env::var(var)
+                .unwrap_or_default()
+                .split(';')
+                .filter(|spec| !spec.trim().is_empty())
+                .map(|spec| Hook::parse(spec).map_err(|e| format!("{}: {}", var, e)))
+                .collect()
+        };
+        Ok(Self {
+            server_controller,
+            backups,
+            pre_stop: read("HOOKS_PRE_STOP")?,
+            post_start: read("HOOKS_POST_START")?,
+            pre_restart: read("HOOKS_PRE_RESTART")?,
+        })
+    }
+
+    fn hooks(&self, event: HookEvent) -> &[Hook] {
+        match event {
+            HookEvent::PreStop => &self.pre_stop,
+            HookEvent::PostStart => &self.post_start,
+            HookEvent::PreRestart => &self.pre_restart,
+        }
+    }
+
+    /// イベントのフックを順に実行する。必須のフックが失敗したらそこで止めてエラーを返す
+    async fn run_hooks(&self, event: HookEvent) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        for hook in self.hooks(event) {
+            println!("Running {} hook: {}", event.name(), hook.describe());
+            if let Err(e) = self.run_hook(event, hook).await {
+                let msg = format!("{} hook {} failed: {}", event.name(), hook.describe(), e);
+                if hook.required {
+                    return Err(msg.into());
+                }
+                eprintln!("{}", msg);
+            }
+        }
+        Ok(())
+    }
+
+    async fn run_hook(&self, event: HookEvent, hook: &Hook) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        match &hook.action {
+            HookAction::Backup => {
+                // 止まっているサーバーはバックアップできないが、再起動は妨げない
+                if !self.server_controller.is_running() {
+                    println!("Server is not running; skipping backup hook.");
+                    return Ok(());
+                }
+                self.backups.create().await?;
+            }
+            HookAction::Announce(message) => {
+                self.server_controller.send_command(&format!("say {}", message))?;
+            }
+            HookAction::Script(command) => {
+                let mut cmd = if cfg!(windows) {
+                    let mut cmd = Command::new("cmd");
+                    cmd.arg("/C").arg(command);
+                    cmd
+                } else {
+                    let mut cmd = Command::new("sh");
+                    cmd.arg("-c").arg(command);
+                    cmd
+                };
+                cmd.env("HOOK_EVENT", event.name()).kill_on_drop(true);
+                let status = tokio::time::timeout(SCRIPT_TIMEOUT, cmd.status())
+                    .await
+                    .map_err(|_| "timed out")??;
+                if !status.success() {
+                    return Err(format!("exited with {}", status).into());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// サーバーを起動し、起動が完了したら post-start のフックを実行する。
+    /// 起動中にプロセスが終了した場合はエラーを返す
+    pub async fn start(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut state = self.server_controller.subscribe_state();
+        let controller = Arc::clone(&self.server_controller);
+        tokio::task::spawn_blocking(move || controller.start()).await??;
+        let ready = tokio::time::timeout(
+            READY_TIMEOUT,
+            state.wait_for(|state| matches!(state, ServerState::Running | ServerState::Stopped)),
+        )
+        .await
+        .map(|state| state.map(|state| *state));
+        match ready {
+            Ok(Ok(ServerState::Stopped)) => {
+                return Err("bedrock_server exited during startup".into());
+            }
+            Ok(_) => {}
+            Err(_) => eprintln!("Server did not report startup in time; running post-start hooks anyway."),
+        }
+        self.run_hooks(HookEvent::PostStart).await
+    }
+
+    /// pre-stop のフックを実行してからサーバーを止める
+    pub async fn stop(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.run_hooks(HookEvent::PreStop).await?;
+        let controller = Arc::clone(&self.server_controller);
+        tokio::task::spawn_blocking(move || controller.stop()).await?;
+        Ok(())
+    }
+
+    /// バックアップを復元する。動いていたサーバーはフックを通して止め、`keep_stopped` でなければ起動し直す
+    pub async fn restore_backup(&self, id: &str, keep_stopped: bool) -> Result<Restored, Box<dyn std::error::Error + Send + Sync>> {
+        let restore = self.backups.prepare_restore(id).await?;
+        let was_running = self.server_controller.state() != ServerState::Stopped;
+        if was_running {
+            self.stop().await?;
+        }
+        let swapped = self.backups.apply_restore(restore).await;
+        let restarted = was_running && !keep_stopped;
+        // 入れ替えに失敗しても元のワールドは戻してあるので、サーバーは起動し直す
+        if restarted {
+            self.start().await?;
+        }
+        Ok(Restored { worlds: swapped?, restarted })
+    }
+
+    /// pre-restart と pre-stop のフックがすべて成功したときだけ再起動する
+    pub async fn restart(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.run_hooks(HookEvent::PreRestart).await?;
+        self.stop().await?;
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        self.start().await
+    }
+}
@@ -0,0 +1,52 @@
+use crate::store::{load_json, save_json};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serenity::all::UserId;
+use std::collections::BTreeMap;
+use std::env;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Link {
+    pub gamertag: String,
+    pub linked_at: DateTime<Utc>,
+}
+
+/// /server で登録したDiscordユーザーとゲーマータグの対応
+pub struct LinkRegistry {
+    path: PathBuf,
+    links: Mutex<BTreeMap<UserId, Link>>,
+}
+
+impl LinkRegistry {
+    /// LINKS_PATH (既定は links.json) に保存する
+    pub fn from_env() -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let path = PathBuf::from(env::var("LINKS_PATH").unwrap_or_else(|_| "links.json".to_string()));
+        let links = load_json(&path)?;
+        Ok(Self {
+            path,
+            links: Mutex::new(links),
+        })
+    }
+
+    pub fn link(&self, user_id: UserId, gamertag: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut links = self.links.lock().unwrap();
+        links.insert(user_id, Link { gamertag: gamertag.to_string(), linked_at: Utc::now() });
+        save_json(&self.path, &*links)
+    }
+
+    pub fn gamertag_for(&self, user_id: UserId) -> Option<String> {
+        self.links.lock().unwrap().get(&user_id).map(|link| link.gamertag.clone())
+    }
+
+    /// ゲーマータグ (大文字小文字は区別しない) に紐づくユーザー
+    pub fn user_for(&self, gamertag: &str) -> Option<(UserId, Link)> {
+        self.links
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|(_, link)| link.gamertag.eq_ignore_ascii_case(gamertag))
+            .map(|(user_id, link)| (*user_id, link.clone()))
+    }
+}
@@ -0,0 +1,73 @@
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// bedrock_server の出力から読み取れるイベント
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ServerEvent {
+    PlayerConnected { name: String, xuid: String },
+    PlayerDisconnected { name: String, xuid: String },
+    Chat { name: String, message: String },
+    Death { message: String },
+}
+
+struct Patterns {
+    prefix: Regex,
+    connected: Regex,
+    disconnected: Regex,
+    chat: Regex,
+    death: Regex,
+}
+
+fn patterns() -> &'static Patterns {
+    static PATTERNS: OnceLock<Patterns> = OnceLock::new();
+    PATTERNS.get_or_init(|| Patterns {
+        // [2024-01-01 12:00:00:000 INFO] のようなログの先頭部分
+        prefix: Regex::new(r"^\[[^\]]*\]\s*").unwrap(),
+        connected: Regex::new(r"^Player connected: (.+?), xuid: (\d*)").unwrap(),
+        disconnected: Regex::new(r"^Player disconnected: (.+?), xuid: (\d*)").unwrap(),
+        chat: Regex::new(r"^(?:\[Chat\]\s*)?<([^>]+)>\s?(.*)$").unwrap(),
+        death: Regex::new(concat!(
+            r"^\S.*? (?:was (?:slain|shot|killed|blown up|fireballed|pummeled|squashed|impaled|pricked|stung|struck by lightning|squished|poked to death)",
+            r"|drowned|died|burned to death|blew up|hit the ground too hard|fell (?:from|off|out of)",
+            r"|starved to death|suffocated in a wall|tried to swim in lava|went up in flames|walked into",
+            r"|experienced kinetic energy|froze to death|withered away|was doomed to fall)",
+        ))
+        .unwrap(),
+    })
+}
+
+/// ログの先頭部分 (日時とログレベル) を取り除く
+pub fn strip_prefix(line: &str) -> &str {
+    match patterns().prefix.find(line) {
+        Some(prefix) => line[prefix.end()..].trim(),
+        None => line.trim(),
+    }
+}
+
+pub fn parse_line(line: &str) -> Option<ServerEvent> {
+    let patterns = patterns();
+    let body = strip_prefix(line);
+
+    if let Some(caps) = patterns.connected.captures(body) {
+        return Some(ServerEvent::PlayerConnected {
+            name: caps[1].to_string(),
+            xuid: caps[2].to_string(),
+        });
+    }
+    if let Some(caps) = patterns.disconnected.captures(body) {
+        return Some(ServerEvent::PlayerDisconnected {
+            name: caps[1].to_string(),
+            xuid: caps[2].to_string(),
+        });
+    }
+    if let Some(caps) = patterns.chat.captures(body) {
+        return Some(ServerEvent::Chat {
+            name: caps[1].to_string(),
+            message: caps[2].to_string(),
+        });
+    }
+    if patterns.death.is_match(body) {
+        return Some(ServerEvent::Death { message: body.to_string() });
+    }
+    None
+}
@@ -1,26 +1,92 @@
+mod addons;
+mod admin_console;
+mod allowlist;
+mod audit;
+mod auth;
+mod backup;
+mod bans;
+mod bridge;
 mod commands;
+mod confirm;
+mod console;
+mod duration;
+mod guests;
+mod i18n;
+mod lifecycle;
+mod links;
+mod log_parser;
+mod maintenance;
+mod permissions;
+mod players;
+mod playtime;
+mod properties;
+mod prune;
+mod rate_limit;
+mod resources;
+mod restart;
+mod schedule;
 mod status;
 mod server_controller;
+mod size;
+mod state;
+mod store;
+mod template;
+mod unzip;
+mod updater;
+mod worlds;
 
 use serenity::all::{
-    Command, Context, EventHandler, GatewayIntents, Interaction, Ready,
+    Command, CommandOptionType, Context, EventHandler, GatewayIntents, Interaction, Message, Ready,
 };
 use serenity::Client;
 use std::env;
 use std::sync::Arc;
-use status::StatusMonitor;
+use addons::Addons;
+use admin_console::AdminConsole;
+use audit::AuditLog;
+use auth::Admins;
+use backup::BackupManager;
+use bans::BanList;
+use bridge::ChatBridge;
+use confirm::Confirmations;
+use console::ConsoleMirror;
+use guests::GuestPasses;
+use lifecycle::Lifecycle;
+use links::LinkRegistry;
+use maintenance::{Maintenance, MaintenanceMode};
+use permissions::{Permission, Permissions};
+use players::PlayerTracker;
+use playtime::{Period, PlaytimeTracker};
+use properties::ServerConfig;
+use prune::{Pruner, MAX_PRUNE_DAYS};
+use rate_limit::RateLimiter;
+use resources::ResourceMonitor;
+use restart::{RestartScheduler, MAX_RESTART_DELAY};
+use state::AppState;
+use status::{StatusMonitor, StatusTemplates};
 use server_controller::ServerController;
+use updater::Updater;
+use worlds::Worlds;
 
 struct Handler {
-    server_controller: Arc<ServerController>,
+    state: Arc<AppState>,
     status_monitor: Arc<StatusMonitor>,
+    admin_console: Option<Arc<AdminConsole>>,
+    chat_bridge: Option<Arc<ChatBridge>>,
 }
 
 impl Handler {
-    fn new(server_controller: Arc<ServerController>, status_monitor: Arc<StatusMonitor>) -> Self {
+    fn new(
+        state: Arc<AppState>,
+        status_monitor: Arc<StatusMonitor>,
+        admin_console: Option<Arc<AdminConsole>>,
+        chat_bridge: Option<Arc<ChatBridge>>,
+    ) -> Self {
         Self {
-            server_controller,
+            state,
             status_monitor,
+            admin_console,
+            chat_bridge,
         }
     }
 }
@@ -29,19 +95,132 @@ impl Handler {
 impl EventHandler for Handler {
     async fn ready(&self, ctx: Context, ready: Ready) {
         println!("{} is connected!", ready.user.name);
-        let lang = env::var("LANGUAGE").unwrap_or_else(|_| "JP".to_string());
-        let is_en = lang.to_uppercase() == "EN";
-        let server_desc = if is_en { "Register to the Minecraft server allowlist" } else { "Minecraftサーバーのallowlistに登録する" };
-        let restart_desc = if is_en { "Restart the Minecraft server" } else { "Minecraftサーバーを再起動する" };
-
-        // -------------------------
-        // 現在restartコマンドの実行権限は限定されていません、everyoneに実行できるようになっています
-        // あなたがもしこのコードをそのまま使用する場合は **絶対に** restartを削除するか権限を限定するようにコードを編集してください
+
         let commands = vec![
-            commands::register("server", server_desc),
-            commands::register("restart", restart_desc),
+            commands::register("server", "cmd-server-desc"),
+            commands::register("start", "cmd-start-desc"),
+            commands::register("stop", "cmd-stop-desc"),
+            commands::register("restart", "cmd-restart-desc")
+                .add_option(
+                    commands::option(CommandOptionType::Integer, "delay", "option-restart-delay")
+                        .min_int_value(1)
+                        .max_int_value(MAX_RESTART_DELAY.as_secs() / 60),
+                )
+                .add_option(commands::option(CommandOptionType::Boolean, "cancel", "option-restart-cancel")),
+            commands::register("players", "cmd-players-desc"),
+            commands::register("stats", "cmd-stats-desc"),
+            commands::register("playtime", "cmd-playtime-desc")
+                .add_option(commands::option(CommandOptionType::String, "player", "option-player")),
+            commands::register("leaderboard", "cmd-leaderboard-desc")
+                .add_option(Period::ALL.into_iter().fold(
+                    commands::option(CommandOptionType::String, "period", "option-period"),
+                    |option, period| commands::choice(option, period.name(), &format!("period-{}", period.name())),
+                )),
+            commands::register("guest", "cmd-guest-desc")
+                .add_option(commands::option(CommandOptionType::String, "gamertag", "option-gamertag").required(true))
+                .add_option(commands::option(CommandOptionType::String, "duration", "option-duration").required(true)),
+            commands::register("allowlist", "cmd-allowlist-desc")
+                .add_option(
+                    commands::option(CommandOptionType::SubCommand, "prune", "cmd-allowlist-prune-desc")
+                        .add_sub_option(
+                            commands::option(CommandOptionType::Integer, "days", "option-days")
+                                .required(true)
+                                .min_int_value(1)
+                                .max_int_value(MAX_PRUNE_DAYS.into()),
+                        )
+                        .add_sub_option(commands::option(CommandOptionType::Boolean, "dry_run", "option-dry-run")),
+                ),
+            commands::register("backup", "cmd-backup-desc")
+                .add_option(commands::option(CommandOptionType::SubCommand, "now", "cmd-backup-now-desc"))
+                .add_option(commands::option(CommandOptionType::SubCommand, "list", "cmd-backup-list-desc"))
+                .add_option(
+                    commands::option(CommandOptionType::SubCommand, "restore", "cmd-backup-restore-desc")
+                        .add_sub_option(commands::option(CommandOptionType::String, "id", "option-backup-id").required(true)),
+                ),
+            commands::register("maintenance", "cmd-maintenance-desc")
+                .add_option(
+                    commands::option(CommandOptionType::SubCommand, "on", "cmd-maintenance-on-desc")
+                        .add_sub_option(commands::option(CommandOptionType::String, "message", "option-maintenance-message"))
+                        .add_sub_option(commands::option(CommandOptionType::String, "eta", "option-maintenance-eta"))
+                        .add_sub_option(MaintenanceMode::ALL.into_iter().fold(
+                            commands::option(CommandOptionType::String, "mode", "option-maintenance-mode"),
+                            |option, mode| commands::choice(option, mode.name(), &format!("maintenance-mode-{}", mode.name())),
+                        )),
+                )
+                .add_option(commands::option(CommandOptionType::SubCommand, "off", "cmd-maintenance-off-desc")),
+            commands::register("update", "cmd-update-desc")
+                .add_option(commands::option(CommandOptionType::SubCommand, "check", "cmd-update-check-desc"))
+                .add_option(
+                    commands::option(CommandOptionType::SubCommand, "install", "cmd-update-install-desc")
+                        .add_sub_option(commands::option(CommandOptionType::String, "source", "option-update-source")),
+                ),
+            commands::register("addon", "cmd-addon-desc")
+                .add_option(
+                    commands::option(CommandOptionType::SubCommand, "install", "cmd-addon-install-desc")
+                        .add_sub_option(commands::option(CommandOptionType::Attachment, "file", "option-addon-file").required(true)),
+                )
+                .add_option(commands::option(CommandOptionType::SubCommand, "list", "cmd-addon-list-desc"))
+                .add_option(
+                    commands::option(CommandOptionType::SubCommand, "remove", "cmd-addon-remove-desc")
+                        .add_sub_option(commands::option(CommandOptionType::String, "pack", "option-addon-pack").required(true)),
+                ),
+            commands::register("world", "cmd-world-desc")
+                .add_option(commands::option(CommandOptionType::SubCommand, "list", "cmd-world-list-desc"))
+                .add_option(
+                    commands::option(CommandOptionType::SubCommand, "switch", "cmd-world-switch-desc")
+                        .add_sub_option(commands::option(CommandOptionType::String, "name", "option-world-name").required(true)),
+                )
+                .add_option(
+                    commands::option(CommandOptionType::SubCommand, "upload", "cmd-world-upload-desc")
+                        .add_sub_option(commands::option(CommandOptionType::Attachment, "file", "option-world-file").required(true))
+                        .add_sub_option(commands::option(CommandOptionType::String, "name", "option-world-new-name")),
+                ),
+            commands::register("config", "cmd-config-desc")
+                .add_option(
+                    commands::option(CommandOptionType::SubCommand, "get", "cmd-config-get-desc")
+                        .add_sub_option(commands::option(CommandOptionType::String, "key", "option-config-key")),
+                )
+                .add_option(
+                    commands::option(CommandOptionType::SubCommand, "set", "cmd-config-set-desc")
+                        .add_sub_option(commands::option(CommandOptionType::String, "key", "option-config-key").required(true))
+                        .add_sub_option(commands::option(CommandOptionType::String, "value", "option-config-value").required(true)),
+                ),
+            commands::register("op", "cmd-op-desc")
+                .add_option(commands::option(CommandOptionType::String, "gamertag", "option-gamertag"))
+                .add_option(commands::option(CommandOptionType::User, "user", "option-linked-user"))
+                .add_option(Permission::ALL.into_iter().fold(
+                    commands::option(CommandOptionType::String, "level", "option-permission-level"),
+                    |option, permission| commands::choice(option, permission.name(), &format!("permission-{}", permission.name())),
+                )),
+            commands::register("deop", "cmd-deop-desc")
+                .add_option(commands::option(CommandOptionType::String, "gamertag", "option-gamertag"))
+                .add_option(commands::option(CommandOptionType::User, "user", "option-linked-user")),
+            commands::register("ops", "cmd-ops-desc"),
+            commands::register("kick", "cmd-kick-desc")
+                .add_option(commands::option(CommandOptionType::String, "gamertag", "option-gamertag"))
+                .add_option(commands::option(CommandOptionType::User, "user", "option-linked-user"))
+                .add_option(commands::option(CommandOptionType::String, "reason", "option-reason")),
+            commands::register("ban", "cmd-ban-desc")
+                .add_option(commands::option(CommandOptionType::String, "gamertag", "option-gamertag"))
+                .add_option(commands::option(CommandOptionType::User, "user", "option-linked-user"))
+                .add_option(commands::option(CommandOptionType::String, "duration", "option-ban-duration"))
+                .add_option(commands::option(CommandOptionType::String, "reason", "option-reason")),
+            commands::register("audit", "cmd-audit-desc")
+                .add_option(
+                    commands::option(CommandOptionType::SubCommand, "search", "cmd-audit-search-desc")
+                        .add_sub_option(commands::option(CommandOptionType::User, "user", "option-audit-user"))
+                        .add_sub_option(commands::option(CommandOptionType::String, "command", "option-audit-command"))
+                        .add_sub_option(commands::option(CommandOptionType::String, "text", "option-audit-text"))
+                        .add_sub_option(
+                            commands::option(CommandOptionType::Integer, "limit", "option-audit-limit")
+                                .min_int_value(1)
+                                .max_int_value(50),
+                        ),
+                ),
+            commands::register("unban", "cmd-unban-desc")
+                .add_option(commands::option(CommandOptionType::String, "gamertag", "option-gamertag"))
+                .add_option(commands::option(CommandOptionType::User, "user", "option-linked-user")),
         ];
-        // -------------------------
 
         if let Err(e) = Command::set_global_commands(&ctx.http, commands).await {
             eprintln!("Error registering commands: {}", e);
@@ -53,18 +232,37 @@ impl EventHandler for Handler {
         println!("Status monitoring started!");
     }
 
+    async fn message(&self, ctx: Context, msg: Message) {
+        if msg.author.bot {
+            return;
+        }
+        if let Some(console) = self.admin_console.as_ref().filter(|c| c.channel_id() == msg.channel_id) {
+            if let Err(e) = console.handle_message(&ctx, &msg).await {
+                eprintln!("Error handling console message: {}", e);
+            }
+        }
+        if let Some(bridge) = self.chat_bridge.as_ref().filter(|b| b.channel_id() == msg.channel_id) {
+            if let Err(e) = bridge.handle_message(&ctx, &msg).await {
+                eprintln!("Error relaying message to the game: {}", e);
+            }
+        }
+    }
+
     async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
         match interaction {
             Interaction::Command(command) => {
-                if let Err(e) = commands::handle_command(&ctx, &command, Arc::clone(&self.server_controller)).await {
+                if let Err(e) = commands::handle_command(&ctx, &command, &self.state).await {
                     eprintln!("Error handling command: {}", e);
                 }
             }
-            Interaction::Modal(modal) => {
-                if modal.data.custom_id == "server_modal" {
-                    if let Err(e) = commands::handle_modal(&ctx, &modal, Arc::clone(&self.server_controller)).await {
-                        eprintln!("Error handling modal: {}", e);
-                    }
+            Interaction::Modal(modal) if modal.data.custom_id == "server_modal" => {
+                if let Err(e) = commands::handle_modal(&ctx, &modal, &self.state).await {
+                    eprintln!("Error handling modal: {}", e);
+                }
+            }
+            Interaction::Component(component) => {
+                if let Err(e) = commands::handle_component(&ctx, &component, &self.state).await {
+                    eprintln!("Error handling button: {}", e);
                 }
             }
             _ => {}
@@ -80,11 +278,154 @@ async fn main() {
         .expect("Expected STATUS_CHANNEL_ID in environment")
         .parse::<u64>()
         .expect("STATUS_CHANNEL_ID must be a valid u64");
+    let status_templates = match StatusTemplates::from_env() {
+        Ok(templates) => templates,
+        Err(e) => {
+            eprintln!("Invalid status template: {}", e);
+            return;
+        }
+    };
     let server_path = env::var("SERVER_PATH").unwrap_or_else(|_| "../".to_string());
     let server_controller = Arc::new(ServerController::new(server_path));
-    if let Err(e) = server_controller.start() {
-        eprintln!("Failed to start bedrock_server: {}", e);
-        return;
+    let console_mirror = match ConsoleMirror::from_env(&server_controller) {
+        Ok(mirror) => mirror,
+        Err(e) => {
+            eprintln!("Invalid console mirror config: {}", e);
+            return;
+        }
+    };
+    let admins = match Admins::from_env() {
+        Ok(admins) => admins,
+        Err(e) => {
+            eprintln!("Invalid admin config: {}", e);
+            return;
+        }
+    };
+    let rate_limiter = match RateLimiter::from_env() {
+        Ok(rate_limiter) => rate_limiter,
+        Err(e) => {
+            eprintln!("Invalid rate limit config: {}", e);
+            return;
+        }
+    };
+    let audit = match AuditLog::from_env() {
+        Ok(audit) => Arc::new(audit),
+        Err(e) => {
+            eprintln!("Invalid audit log config: {}", e);
+            return;
+        }
+    };
+    let admin_console = match AdminConsole::from_env(admins.clone(), Arc::clone(&server_controller), Arc::clone(&audit)) {
+        Ok(console) => console.map(Arc::new),
+        Err(e) => {
+            eprintln!("Invalid admin console config: {}", e);
+            return;
+        }
+    };
+    let chat_bridge = match ChatBridge::from_env(Arc::clone(&server_controller)) {
+        Ok(bridge) => bridge.map(Arc::new),
+        Err(e) => {
+            eprintln!("Invalid chat bridge config: {}", e);
+            return;
+        }
+    };
+    let players = match PlayerTracker::from_env(Arc::clone(&server_controller)) {
+        Ok(tracker) => Arc::new(tracker),
+        Err(e) => {
+            eprintln!("Invalid player tracker config: {}", e);
+            return;
+        }
+    };
+    let playtime = match PlaytimeTracker::from_env(Arc::clone(&server_controller)) {
+        Ok(tracker) => Arc::new(tracker),
+        Err(e) => {
+            eprintln!("Failed to load playtime: {}", e);
+            return;
+        }
+    };
+    let links = match LinkRegistry::from_env() {
+        Ok(links) => Arc::new(links),
+        Err(e) => {
+            eprintln!("Failed to load links: {}", e);
+            return;
+        }
+    };
+    let pruner = match Pruner::from_env(Arc::clone(&playtime), Arc::clone(&links), Arc::clone(&server_controller)) {
+        Ok(pruner) => Arc::new(pruner),
+        Err(e) => {
+            eprintln!("Invalid prune config: {}", e);
+            return;
+        }
+    };
+    let guests = match GuestPasses::from_env(Arc::clone(&server_controller), Arc::clone(&players), Arc::clone(&audit)) {
+        Ok(guests) => Arc::new(guests),
+        Err(e) => {
+            eprintln!("Failed to load guest passes: {}", e);
+            return;
+        }
+    };
+    let bans = match BanList::from_env(Arc::clone(&server_controller)) {
+        Ok(bans) => Arc::new(bans),
+        Err(e) => {
+            eprintln!("Failed to load bans: {}", e);
+            return;
+        }
+    };
+    let backups = match BackupManager::from_env(Arc::clone(&server_controller)) {
+        Ok(backups) => Arc::new(backups),
+        Err(e) => {
+            eprintln!("Invalid backup config: {}", e);
+            return;
+        }
+    };
+    let lifecycle = match Lifecycle::from_env(Arc::clone(&server_controller), Arc::clone(&backups)) {
+        Ok(lifecycle) => Arc::new(lifecycle),
+        Err(e) => {
+            eprintln!("Invalid hook config: {}", e);
+            return;
+        }
+    };
+    let permissions = Arc::new(Permissions::from_env(Arc::clone(&playtime), Arc::clone(&server_controller)));
+    let maintenance = match Maintenance::from_env(
+        Arc::clone(&server_controller),
+        Arc::clone(&lifecycle),
+        Arc::clone(&players),
+        Arc::clone(&permissions),
+    ) {
+        Ok(maintenance) => Arc::new(maintenance),
+        Err(e) => {
+            eprintln!("Failed to load maintenance state: {}", e);
+            return;
+        }
+    };
+    let restart = match RestartScheduler::from_env(
+        Arc::clone(&server_controller),
+        Arc::clone(&lifecycle),
+        Arc::clone(&maintenance),
+    ) {
+        Ok(restart) => Arc::new(restart),
+        Err(e) => {
+            eprintln!("Invalid restart schedule: {}", e);
+            return;
+        }
+    };
+    let resources = match ResourceMonitor::from_env(Arc::clone(&server_controller), Arc::clone(&restart), Arc::clone(&audit)) {
+        Ok(resources) => Arc::new(resources),
+        Err(e) => {
+            eprintln!("Invalid resource monitor config: {}", e);
+            return;
+        }
+    };
+    // 起動に失敗してもボットは動かし続け、/start で起動し直せるようにする
+    let autostart = env::var("SERVER_AUTOSTART").map(|value| value != "false").unwrap_or(true);
+    if maintenance.keeps_stopped() {
+        println!("Maintenance is in progress; leaving the server stopped.");
+    } else if autostart {
+        if let Err(e) = server_controller.start() {
+            eprintln!("Failed to start bedrock_server: {}", e);
+        }
+    } else {
+        println!("SERVER_AUTOSTART is false; waiting for /start.");
     }
 
     let display_ip = env::var("SERVER_IP").unwrap_or_else(|_| "127.0.0.1".to_string());
@@ -93,12 +434,55 @@ async fn main() {
         .unwrap_or_else(|_| "19132".to_string())
         .parse::<u16>()
         .expect("SERVER_PORT must be a valid u16");
+    let updater = match Updater::from_env(
+        Arc::clone(&server_controller),
+        Arc::clone(&lifecycle),
+        Arc::clone(&backups),
+        Arc::clone(&audit),
+        connect_ip.clone(),
+        server_port,
+    ) {
+        Ok(updater) => Arc::new(updater),
+        Err(e) => {
+            eprintln!("Invalid updater config: {}", e);
+            return;
+        }
+    };
+    let config = Arc::new(ServerConfig::from_env(Arc::clone(&server_controller)));
+    let addons = Arc::new(Addons::from_env(&server_controller, Arc::clone(&config)));
+    let worlds = Arc::new(Worlds::from_env(Arc::clone(&server_controller), Arc::clone(&lifecycle), Arc::clone(&config)));
+
+    let state = Arc::new(AppState {
+        server_controller: Arc::clone(&server_controller),
+        players: Arc::clone(&players),
+        playtime: Arc::clone(&playtime),
+        links,
+        pruner: Arc::clone(&pruner),
+        guests: Arc::clone(&guests),
+        lifecycle,
+        restart: Arc::clone(&restart),
+        resources: Arc::clone(&resources),
+        updater: Arc::clone(&updater),
+        backups: Arc::clone(&backups),
+        config,
+        addons,
+        worlds,
+        bans,
+        audit: Arc::clone(&audit),
+        rate_limiter,
+        confirmations: Confirmations::default(),
+        permissions,
+        maintenance: Arc::clone(&maintenance),
+        admins,
+    });
 
     let status_monitor = Arc::new(StatusMonitor::new(
         channel_id,
         display_ip,
         connect_ip,
         server_port,
+        status_templates,
+        Arc::clone(&state),
     ));
 
     let intents = GatewayIntents::GUILD_MESSAGES
@@ -106,9 +490,32 @@ async fn main() {
         | GatewayIntents::GUILDS;
 
     let mut client = Client::builder(&token, intents)
-        .event_handler(Handler::new(Arc::clone(&server_controller), status_monitor))
+        .event_handler(Handler::new(
+            state,
+            status_monitor,
+            admin_console,
+            chat_bridge.clone(),
+        ))
         .await
         .expect("Error creating client");
+    audit.start(Arc::clone(&client.http));
+    players.start(Arc::clone(&client.http));
+    playtime.start();
+    pruner.start_schedule(Arc::clone(&client.http));
+    guests.start();
+    maintenance.start();
+    restart.start_schedule();
+    resources.start(Arc::clone(&client.http));
+    backups.start_schedule();
+    updater.start_schedule();
+    if let Some(mirror) = console_mirror {
+        mirror.start(Arc::clone(&client.http));
+        println!("Console mirroring started!");
+    }
+    if let Some(bridge) = &chat_bridge {
+        bridge.start(Arc::clone(&client.http));
+        println!("Chat bridge started!");
+    }
     {
         let console_controller = Arc::clone(&server_controller);
         std::thread::spawn(move || {
@@ -0,0 +1,219 @@
+use crate::lifecycle::Lifecycle;
+use crate::log_parser::{parse_line, ServerEvent};
+use crate::permissions::Permissions;
+use crate::players::PlayerTracker;
+use crate::server_controller::{ServerController, ServerState};
+use crate::store::{load_json, save_json};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serenity::all::UserId;
+use std::env;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+
+/// 接続直後はキックできないことがあるので少し待つ
+const KICK_DELAY: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MaintenanceMode {
+    /// サーバーを停止する
+    Stop,
+    /// サーバーは動かしたまま、オペレーター以外をキックする
+    Lock,
+}
+
+impl MaintenanceMode {
+    pub const ALL: [MaintenanceMode; 2] = [MaintenanceMode::Stop, MaintenanceMode::Lock];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            MaintenanceMode::Stop => "stop",
+            MaintenanceMode::Lock => "lock",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|mode| mode.name() == name)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaintenanceInfo {
+    pub mode: MaintenanceMode,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// 終了の目安
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub eta: Option<DateTime<Utc>>,
+    pub started_by: UserId,
+    pub started_at: DateTime<Utc>,
+}
+
+/// メンテナンス中に /server で受け付けた登録
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedRegistration {
+    pub gamertag: String,
+    pub user_id: UserId,
+    pub queued_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct MaintenanceData {
+    #[serde(default)]
+    active: Option<MaintenanceInfo>,
+    #[serde(default)]
+    queue: Vec<QueuedRegistration>,
+}
+
+/// メンテナンスモード。ボットを再起動しても続くようにファイルに保存する
+pub struct Maintenance {
+    path: PathBuf,
+    data: Mutex<MaintenanceData>,
+    server_controller: Arc<ServerController>,
+    lifecycle: Arc<Lifecycle>,
+    players: Arc<PlayerTracker>,
+    permissions: Arc<Permissions>,
+}
+
+impl Maintenance {
+    /// MAINTENANCE_PATH (既定は maintenance.json) に保存する
+    pub fn from_env(
+        server_controller: Arc<ServerController>,
+        lifecycle: Arc<Lifecycle>,
+        players: Arc<PlayerTracker>,
+        permissions: Arc<Permissions>,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let path = PathBuf::from(env::var("MAINTENANCE_PATH").unwrap_or_else(|_| "maintenance.json".to_string()));
+        let data = load_json(&path)?;
+        Ok(Self {
+            path,
+            data: Mutex::new(data),
+            server_controller,
+            lifecycle,
+            players,
+            permissions,
+        })
+    }
+
+    pub fn current(&self) -> Option<MaintenanceInfo> {
+        self.data.lock().unwrap().active.clone()
+    }
+
+    /// サーバーを止めておくメンテナンス中か (起動や再起動をしない)
+    pub fn keeps_stopped(&self) -> bool {
+        self.current().is_some_and(|info| info.mode == MaintenanceMode::Stop)
+    }
+
+    /// メンテナンスを始める。すでにメンテナンス中なら false。
+    /// 告知やサーバーの停止に失敗したらメンテナンス中の記録を取り消す
+    pub async fn begin(&self, info: MaintenanceInfo) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        {
+            let mut data = self.data.lock().unwrap();
+            if data.active.is_some() {
+                return Ok(false);
+            }
+            data.active = Some(info.clone());
+            save_json(&self.path, &*data)?;
+        }
+        if let Err(e) = self.enter(&info).await {
+            let mut data = self.data.lock().unwrap();
+            data.active = None;
+            if let Err(save) = save_json(&self.path, &*data) {
+                eprintln!("Failed to save maintenance state after a failed start: {}", save);
+            }
+            return Err(e);
+        }
+        println!("Maintenance started ({})", info.mode.name());
+        Ok(true)
+    }
+
+    /// 理由を告知し、モードに合わせてサーバーを止めるかプレイヤーをキックする
+    async fn enter(&self, info: &MaintenanceInfo) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if self.server_controller.is_running() {
+            if let Some(reason) = &info.reason {
+                self.server_controller.send_command(&format!("say {}", reason))?;
+            }
+        }
+        match info.mode {
+            MaintenanceMode::Stop if self.server_controller.state() != ServerState::Stopped => self.lifecycle.stop().await?,
+            MaintenanceMode::Stop => {}
+            MaintenanceMode::Lock => {
+                for player in self.players.online().await {
+                    self.kick_unless_operator(&player.name, None, info.reason.as_deref()).await;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// メンテナンスを終える。停止していたサーバーは起動し、起動できた場合だけ終了して
+    /// 待っていた登録を返す。メンテナンス中でなければ None
+    pub async fn end(&self) -> Result<Option<Vec<QueuedRegistration>>, Box<dyn std::error::Error + Send + Sync>> {
+        let Some(info) = self.current() else {
+            return Ok(None);
+        };
+        if info.mode == MaintenanceMode::Stop && self.server_controller.state() == ServerState::Stopped {
+            self.lifecycle.start().await?;
+        }
+        let mut data = self.data.lock().unwrap();
+        data.active = None;
+        let queue = std::mem::take(&mut data.queue);
+        save_json(&self.path, &*data)?;
+        println!("Maintenance ended; {} queued registrations", queue.len());
+        Ok(Some(queue))
+    }
+
+    /// メンテナンス後に登録する。同じユーザーかゲーマータグの登録は置き換える
+    pub fn enqueue(&self, gamertag: &str, user_id: UserId) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut data = self.data.lock().unwrap();
+        data.queue.retain(|queued| queued.user_id != user_id && !queued.gamertag.eq_ignore_ascii_case(gamertag));
+        data.queue.push(QueuedRegistration {
+            gamertag: gamertag.to_string(),
+            user_id,
+            queued_at: Utc::now(),
+        });
+        save_json(&self.path, &*data)
+    }
+
+    async fn kick_unless_operator(&self, name: &str, xuid: Option<String>, reason: Option<&str>) {
+        let xuid = match xuid {
+            Some(xuid) => Some(xuid),
+            None => self.permissions.resolve_xuid(name).await,
+        };
+        if xuid.is_some_and(|xuid| self.permissions.is_operator(&xuid)) {
+            return;
+        }
+        if let Err(e) = self.server_controller.kick(name, reason) {
+            eprintln!("Failed to kick {} for maintenance: {}", name, e);
+        }
+    }
+
+    /// ロック中に接続してきたオペレーター以外のプレイヤーをキックする
+    pub fn start(self: &Arc<Self>) {
+        let maintenance = Arc::clone(self);
+        let mut output = self.server_controller.subscribe_output();
+        tokio::spawn(async move {
+            loop {
+                let line = match output.recv().await {
+                    Ok(line) => line,
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => break,
+                };
+                let Some(ServerEvent::PlayerConnected { name, xuid }) = parse_line(&line) else {
+                    continue;
+                };
+                let Some(info) = maintenance.current().filter(|info| info.mode == MaintenanceMode::Lock) else {
+                    continue;
+                };
+                let maintenance = Arc::clone(&maintenance);
+                tokio::spawn(async move {
+                    tokio::time::sleep(KICK_DELAY).await;
+                    maintenance.kick_unless_operator(&name, Some(xuid), info.reason.as_deref()).await;
+                });
+            }
+        });
+    }
+}
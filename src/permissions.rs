@@ -0,0 +1,160 @@
+use crate::allowlist::Allowlist;
+use crate::playtime::PlaytimeTracker;
+use crate::server_controller::ServerController;
+use crate::store::{load_json, save_json};
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// `op` / `deop` の応答を待つ時間 (出力がこの間途切れたら終わり)
+const OP_RESPONSE_QUIET: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Permission {
+    Visitor,
+    Member,
+    Operator,
+}
+
+impl Permission {
+    pub const ALL: [Permission; 3] = [Permission::Visitor, Permission::Member, Permission::Operator];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Permission::Visitor => "visitor",
+            Permission::Member => "member",
+            Permission::Operator => "operator",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|permission| permission.name() == name)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PermissionEntry {
+    permission: Permission,
+    xuid: String,
+}
+
+/// permissions.json の1件。名前は接続ログか allowlist から分かったときだけ入る
+#[derive(Debug, Clone)]
+pub struct PlayerPermission {
+    pub xuid: String,
+    pub name: Option<String>,
+    pub permission: Permission,
+}
+
+/// サーバーの permissions.json (XUID ごとの権限)
+pub struct Permissions {
+    path: PathBuf,
+    allowlist: Allowlist,
+    playtime: Arc<PlaytimeTracker>,
+    server_controller: Arc<ServerController>,
+    // 読み込みから書き込みまでの間に他の変更が割り込まないようにする
+    lock: Mutex<()>,
+}
+
+impl Permissions {
+    /// PERMISSIONS_PATH (既定はサーバーのフォルダの permissions.json)
+    pub fn from_env(playtime: Arc<PlaytimeTracker>, server_controller: Arc<ServerController>) -> Self {
+        let path = match env::var("PERMISSIONS_PATH") {
+            Ok(path) => PathBuf::from(path),
+            Err(_) => server_controller.server_dir().join("permissions.json"),
+        };
+        Self {
+            path,
+            allowlist: Allowlist::from_env(&server_controller),
+            playtime,
+            server_controller,
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// ゲーマータグの XUID を接続ログ、なければ allowlist.json (サーバーが初回接続時に書き込む) から探す
+    pub async fn resolve_xuid(&self, gamertag: &str) -> Option<String> {
+        if let Some(xuid) = self.playtime.xuid_for(gamertag).await {
+            return Some(xuid);
+        }
+        self.allowlist
+            .entries()
+            .ok()?
+            .into_iter()
+            .find(|entry| entry.name.eq_ignore_ascii_case(gamertag))
+            .and_then(|entry| entry.xuid)
+            .filter(|xuid| !xuid.is_empty())
+    }
+
+    async fn name_for(&self, xuid: &str) -> Option<String> {
+        if let Some(name) = self.playtime.name_for(xuid).await {
+            return Some(name);
+        }
+        self.allowlist
+            .entries()
+            .ok()?
+            .into_iter()
+            .find(|entry| entry.xuid.as_deref() == Some(xuid))
+            .map(|entry| entry.name)
+    }
+
+    pub async fn list(&self) -> Result<Vec<PlayerPermission>, Box<dyn std::error::Error + Send + Sync>> {
+        let entries: Vec<PermissionEntry> = load_json(&self.path)?;
+        let mut permissions = Vec::new();
+        for entry in entries {
+            permissions.push(PlayerPermission {
+                name: self.name_for(&entry.xuid).await,
+                xuid: entry.xuid,
+                permission: entry.permission,
+            });
+        }
+        Ok(permissions)
+    }
+
+    pub fn is_operator(&self, xuid: &str) -> bool {
+        load_json::<Vec<PermissionEntry>>(&self.path)
+            .map(|entries| entries.iter().any(|entry| entry.xuid == xuid && entry.permission == Permission::Operator))
+            .unwrap_or(false)
+    }
+
+    /// 権限を書き込み、サーバーが動いていれば `op` / `deop` で反映する。
+    /// `None` なら permissions.json から削除する (server.properties の既定の権限に戻る)。
+    /// コマンドの応答 (`Opped: ...` / `De-opped: ...`、すでにその権限の場合も含む) を確かめ、反映できた場合は true。
+    /// オフラインのプレイヤーなどは次の起動から
+    pub async fn set(
+        &self,
+        gamertag: &str,
+        xuid: &str,
+        permission: Option<Permission>,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        {
+            let _lock = self.lock.lock().unwrap();
+            let mut entries: Vec<PermissionEntry> = load_json(&self.path)?;
+            entries.retain(|entry| entry.xuid != xuid);
+            if let Some(permission) = permission {
+                entries.push(PermissionEntry { permission, xuid: xuid.to_string() });
+            }
+            save_json(&self.path, &entries)?;
+        }
+        if !self.server_controller.is_running() {
+            return Ok(false);
+        }
+        let command = match permission {
+            Some(Permission::Operator) => "op",
+            Some(Permission::Member) | None => "deop",
+            // visitor にするコマンドはないので次の起動から
+            Some(Permission::Visitor) => return Ok(false),
+        };
+        let lines = self
+            .server_controller
+            .send_command_capture(&format!("{} \"{}\"", command, gamertag), OP_RESPONSE_QUIET)
+            .await?;
+        Ok(lines.iter().any(|line| {
+            let line = line.to_lowercase();
+            line.contains("opped:") || line.contains("already")
+        }))
+    }
+}
@@ -0,0 +1,103 @@
+use crate::i18n::{tr, Language};
+use crate::log_parser::{parse_line, ServerEvent};
+use crate::server_controller::ServerController;
+use chrono::{DateTime, Local};
+use serenity::all::{ChannelId, CreateAllowedMentions, CreateMessage, Http};
+use std::collections::BTreeMap;
+use std::env;
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::RwLock;
+
+#[derive(Debug, Clone)]
+pub struct OnlinePlayer {
+    pub name: String,
+    pub since: DateTime<Local>,
+}
+
+/// 接続中のプレイヤーをサーバーの出力から追跡する
+pub struct PlayerTracker {
+    players: RwLock<BTreeMap<String, OnlinePlayer>>,
+    announce_channel: Option<ChannelId>,
+    server_controller: Arc<ServerController>,
+}
+
+impl PlayerTracker {
+    /// PLAYER_EVENTS_CHANNEL_ID を指定すると参加・退出を通知する
+    pub fn from_env(server_controller: Arc<ServerController>) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let announce_channel = match env::var("PLAYER_EVENTS_CHANNEL_ID") {
+            Ok(id) => Some(ChannelId::new(
+                id.parse::<u64>().map_err(|_| "PLAYER_EVENTS_CHANNEL_ID must be a valid u64")?,
+            )),
+            Err(_) => None,
+        };
+        Ok(Self {
+            players: RwLock::new(BTreeMap::new()),
+            announce_channel,
+            server_controller,
+        })
+    }
+
+    /// 接続順に並べたプレイヤー一覧
+    pub async fn online(&self) -> Vec<OnlinePlayer> {
+        let mut players: Vec<_> = self.players.read().await.values().cloned().collect();
+        players.sort_by_key(|player| player.since);
+        players
+    }
+
+    pub async fn is_online(&self, name: &str) -> bool {
+        self.players.read().await.contains_key(&name.to_lowercase())
+    }
+
+    pub fn start(self: &Arc<Self>, http: Arc<Http>) {
+        let tracker = Arc::clone(self);
+        let mut output = self.server_controller.subscribe_output();
+        let mut state = self.server_controller.subscribe_state();
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    line = output.recv() => match line {
+                        Ok(line) => {
+                            if let Some(event) = parse_line(&line) {
+                                tracker.handle_event(&http, event).await;
+                            }
+                        }
+                        Err(RecvError::Lagged(_)) => continue,
+                        Err(RecvError::Closed) => break,
+                    },
+                    changed = state.changed() => {
+                        if changed.is_err() {
+                            break;
+                        }
+                        // 起動・停止・クラッシュのいずれでも接続はすべて切れている
+                        tracker.players.write().await.clear();
+                    }
+                }
+            }
+        });
+    }
+
+    async fn handle_event(&self, http: &Http, event: ServerEvent) {
+        let lang = Language::from_env();
+        let announcement = match event {
+            ServerEvent::PlayerConnected { name, .. } => {
+                let player = OnlinePlayer { name: name.clone(), since: Local::now() };
+                self.players.write().await.insert(name.to_lowercase(), player);
+                tr!(lang, "player-joined", player = name)
+            }
+            ServerEvent::PlayerDisconnected { name, .. } => {
+                self.players.write().await.remove(&name.to_lowercase());
+                tr!(lang, "player-left", player = name)
+            }
+            _ => return,
+        };
+        if let Some(channel_id) = self.announce_channel {
+            let builder = CreateMessage::new()
+                .content(announcement)
+                .allowed_mentions(CreateAllowedMentions::new());
+            if let Err(e) = channel_id.send_message(http, builder).await {
+                eprintln!("Failed to announce player event: {}", e);
+            }
+        }
+    }
+}
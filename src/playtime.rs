@@ -0,0 +1,267 @@
+use crate::log_parser::{parse_line, ServerEvent};
+use crate::server_controller::ServerController;
+use crate::store::{load_json, save_json};
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::Mutex;
+
+/// クラッシュ時にどこまで記録されていたかが分かるよう、この間隔で保存する
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    Day,
+    Week,
+    Month,
+    All,
+}
+
+impl Period {
+    pub const ALL: [Period; 4] = [Period::Day, Period::Week, Period::Month, Period::All];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Period::Day => "day",
+            Period::Week => "week",
+            Period::Month => "month",
+            Period::All => "all",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|period| period.name() == name)
+    }
+
+    /// 集計の開始時刻 (直近24時間・7日・30日)
+    fn since(self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            Period::Day => Some(now - ChronoDuration::days(1)),
+            Period::Week => Some(now - ChronoDuration::days(7)),
+            Period::Month => Some(now - ChronoDuration::days(30)),
+            Period::All => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Session {
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PlayerRecord {
+    name: String,
+    #[serde(default)]
+    sessions: Vec<Session>,
+    #[serde(default)]
+    open_since: Option<DateTime<Utc>>,
+}
+
+impl PlayerRecord {
+    fn last_seen(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        if self.open_since.is_some() {
+            return Some(now);
+        }
+        self.sessions.iter().map(|session| session.end).max()
+    }
+
+    fn total(&self, since: Option<DateTime<Utc>>, now: DateTime<Utc>) -> ChronoDuration {
+        let open = self.open_since.map(|start| Session { start, end: now });
+        self.sessions
+            .iter()
+            .chain(open.as_ref())
+            .map(|session| {
+                let start = since.map_or(session.start, |since| session.start.max(since));
+                (session.end - start).max(ChronoDuration::zero())
+            })
+            .sum()
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PlaytimeData {
+    /// XUID (オフラインモードでは "name:<小文字の名前>") ごとの記録
+    #[serde(default)]
+    players: BTreeMap<String, PlayerRecord>,
+    /// 最後に保存した時刻。起動時に閉じられていないセッションの終了時刻として使う
+    #[serde(default)]
+    updated_at: Option<DateTime<Utc>>,
+}
+
+impl PlaytimeData {
+    fn close_open_sessions(&mut self, end: DateTime<Utc>) {
+        for record in self.players.values_mut() {
+            if let Some(start) = record.open_since.take() {
+                record.sessions.push(Session { start, end: end.max(start) });
+            }
+        }
+    }
+}
+
+pub struct PlaytimeEntry {
+    pub name: String,
+    pub total: Duration,
+}
+
+/// 接続・切断のログからプレイ時間を記録する
+pub struct PlaytimeTracker {
+    path: PathBuf,
+    data: Mutex<PlaytimeData>,
+    server_controller: Arc<ServerController>,
+}
+
+impl PlaytimeTracker {
+    /// PLAYTIME_PATH (既定は playtime.json) に保存する
+    pub fn from_env(server_controller: Arc<ServerController>) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let path = PathBuf::from(env::var("PLAYTIME_PATH").unwrap_or_else(|_| "playtime.json".to_string()));
+        let mut data: PlaytimeData = load_json(&path)?;
+        // 前回ボットごと落ちた場合、最後に保存した時刻でセッションを閉じる
+        let last_saved = data.updated_at.unwrap_or_else(Utc::now);
+        data.close_open_sessions(last_saved);
+        save_json(&path, &data)?;
+        Ok(Self {
+            path,
+            data: Mutex::new(data),
+            server_controller,
+        })
+    }
+
+    pub fn start(self: &Arc<Self>) {
+        let tracker = Arc::clone(self);
+        let mut output = self.server_controller.subscribe_output();
+        let mut state = self.server_controller.subscribe_state();
+        tokio::spawn(async move {
+            let mut heartbeat = tokio::time::interval(HEARTBEAT_INTERVAL);
+            loop {
+                tokio::select! {
+                    line = output.recv() => match line {
+                        Ok(line) => {
+                            if let Some(event) = parse_line(&line) {
+                                tracker.handle_event(event).await;
+                            }
+                        }
+                        Err(RecvError::Lagged(_)) => continue,
+                        Err(RecvError::Closed) => break,
+                    },
+                    changed = state.changed() => {
+                        if changed.is_err() {
+                            break;
+                        }
+                        // サーバーが停止・クラッシュしたら接続中のセッションを閉じる
+                        let mut data = tracker.data.lock().await;
+                        data.close_open_sessions(Utc::now());
+                        tracker.save(&mut data);
+                    }
+                    _ = heartbeat.tick() => {
+                        let mut data = tracker.data.lock().await;
+                        tracker.save(&mut data);
+                    }
+                }
+            }
+        });
+    }
+
+    async fn handle_event(&self, event: ServerEvent) {
+        let now = Utc::now();
+        let mut data = self.data.lock().await;
+        match event {
+            ServerEvent::PlayerConnected { name, xuid } => {
+                let record = data
+                    .players
+                    .entry(player_key(&name, &xuid))
+                    .or_insert_with(|| PlayerRecord { name: name.clone(), sessions: Vec::new(), open_since: None });
+                record.name = name;
+                if let Some(start) = record.open_since.replace(now) {
+                    record.sessions.push(Session { start, end: now });
+                }
+            }
+            ServerEvent::PlayerDisconnected { name, xuid } => {
+                if let Some(record) = data.players.get_mut(&player_key(&name, &xuid)) {
+                    if let Some(start) = record.open_since.take() {
+                        record.sessions.push(Session { start, end: now });
+                    }
+                }
+            }
+            _ => return,
+        }
+        self.save(&mut data);
+    }
+
+    fn save(&self, data: &mut PlaytimeData) {
+        data.updated_at = Some(Utc::now());
+        if let Err(e) = save_json(&self.path, data) {
+            eprintln!("Failed to save playtime: {}", e);
+        }
+    }
+
+    /// 期間内のプレイ時間が長い順
+    pub async fn leaderboard(&self, period: Period) -> Vec<PlaytimeEntry> {
+        let now = Utc::now();
+        let since = period.since(now);
+        let data = self.data.lock().await;
+        let mut entries: Vec<_> = data
+            .players
+            .values()
+            .map(|record| PlaytimeEntry {
+                name: record.name.clone(),
+                total: record.total(since, now).to_std().unwrap_or_default(),
+            })
+            .filter(|entry| !entry.total.is_zero())
+            .collect();
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.total));
+        entries
+    }
+
+    /// 名前 (大文字小文字は区別しない) から各期間のプレイ時間を求める
+    pub async fn player_totals(&self, name: &str) -> Option<(String, Vec<(Period, Duration)>)> {
+        let now = Utc::now();
+        let data = self.data.lock().await;
+        let record = data.players.values().find(|record| record.name.eq_ignore_ascii_case(name))?;
+        let totals = Period::ALL
+            .into_iter()
+            .map(|period| (period, record.total(period.since(now), now).to_std().unwrap_or_default()))
+            .collect();
+        Some((record.name.clone(), totals))
+    }
+
+    /// 最後に接続していた時刻 (接続中なら現在時刻)
+    pub async fn last_seen(&self, name: &str) -> Option<DateTime<Utc>> {
+        let now = Utc::now();
+        let data = self.data.lock().await;
+        data.players
+            .values()
+            .filter(|record| record.name.eq_ignore_ascii_case(name))
+            .filter_map(|record| record.last_seen(now))
+            .max()
+    }
+
+    /// 接続ログに記録された XUID (同じ名前が複数あれば最後に接続していたもの)
+    pub async fn xuid_for(&self, name: &str) -> Option<String> {
+        let now = Utc::now();
+        let data = self.data.lock().await;
+        data.players
+            .iter()
+            .filter(|(key, record)| !key.starts_with("name:") && record.name.eq_ignore_ascii_case(name))
+            .max_by_key(|(_, record)| record.last_seen(now))
+            .map(|(xuid, _)| xuid.clone())
+    }
+
+    pub async fn name_for(&self, xuid: &str) -> Option<String> {
+        self.data.lock().await.players.get(xuid).map(|record| record.name.clone())
+    }
+}
+
+fn player_key(name: &str, xuid: &str) -> String {
+    if xuid.is_empty() {
+        format!("name:{}", name.to_lowercase())
+    } else {
+        xuid.to_string()
+    }
+}
@@ -0,0 +1,270 @@
+use crate::server_controller::ServerController;
+use crate::store::write_atomic;
+use std::env;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// level-name が設定されていないときのワールド名 (bedrock_server の既定値)
+const DEFAULT_LEVEL_NAME: &str = "Bedrock level";
+
+/// server.properties の1行。値を変えていない行は元の文字列のまま書き戻す
+#[derive(Debug, Clone)]
+enum Line {
+    Entry { key: String, value: String, raw: Option<String> },
+    Other(String),
+}
+
+/// コメントや行の順番を保ったまま読み書きする server.properties
+#[derive(Debug, Clone)]
+pub struct Properties {
+    lines: Vec<Line>,
+    newline: &'static str,
+}
+
+impl Properties {
+    pub fn parse(text: &str) -> Self {
+        let newline = if text.contains("\r\n") { "\r\n" } else { "\n" };
+        let lines = text
+            .lines()
+            .map(|line| {
+                let trimmed = line.trim_start();
+                if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with('!') {
+                    return Line::Other(line.to_string());
+                }
+                match trimmed.split_once('=') {
+                    Some((key, value)) => Line::Entry {
+                        key: key.trim().to_string(),
+                        value: value.trim().to_string(),
+                        raw: Some(line.to_string()),
+                    },
+                    None => Line::Other(line.to_string()),
+                }
+            })
+            .collect();
+        Self { lines, newline }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.lines.iter().find_map(|line| match line {
+            Line::Entry { key: k, value, .. } if k == key => Some(value.as_str()),
+            _ => None,
+        })
+    }
+
+    /// 値を変更する。キーがなければ末尾に追加する
+    pub fn set(&mut self, key: &str, value: &str) {
+        for line in &mut self.lines {
+            if let Line::Entry { key: k, value: v, raw } = line {
+                if k == key {
+                    *v = value.to_string();
+                    *raw = None;
+                    return;
+                }
+            }
+        }
+        self.lines.push(Line::Entry { key: key.to_string(), value: value.to_string(), raw: None });
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.lines.iter().filter_map(|line| match line {
+            Line::Entry { key, value, .. } => Some((key.as_str(), value.as_str())),
+            Line::Other(_) => None,
+        })
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        for line in &self.lines {
+            match line {
+                Line::Entry { raw: Some(raw), .. } | Line::Other(raw) => text.push_str(raw),
+                Line::Entry { key, value, raw: None } => {
+                    text.push_str(key);
+                    text.push('=');
+                    text.push_str(value);
+                }
+            }
+            text.push_str(self.newline);
+        }
+        text
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Kind {
+    Bool,
+    Int { min: i64, max: i64 },
+    Choice(&'static [&'static str]),
+    Text,
+}
+
+/// 型の分かっている設定と、再起動せずに反映するためのコマンド
+struct KnownKey {
+    key: &'static str,
+    kind: Kind,
+    live: Option<fn(&str) -> String>,
+}
+
+const KNOWN_KEYS: &[KnownKey] = &[
+    KnownKey { key: "server-name", kind: Kind::Text, live: None },
+    KnownKey {
+        key: "gamemode",
+        kind: Kind::Choice(&["survival", "creative", "adventure"]),
+        live: Some(|value| format!("defaultgamemode {}", value)),
+    },
+    KnownKey { key: "force-gamemode", kind: Kind::Bool, live: None },
+    KnownKey {
+        key: "difficulty",
+        kind: Kind::Choice(&["peaceful", "easy", "normal", "hard"]),
+        live: Some(|value| format!("difficulty {}", value)),
+    },
+    KnownKey { key: "allow-cheats", kind: Kind::Bool, live: None },
+    KnownKey { key: "max-players", kind: Kind::Int { min: 1, max: 1000 }, live: None },
+    KnownKey { key: "online-mode", kind: Kind::Bool, live: None },
+    KnownKey {
+        key: "allow-list",
+        kind: Kind::Bool,
+        live: Some(|value| format!("allowlist {}", if value == "true" { "on" } else { "off" })),
+    },
+    KnownKey { key: "server-port", kind: Kind::Int { min: 1, max: 65535 }, live: None },
+    KnownKey { key: "server-portv6", kind: Kind::Int { min: 1, max: 65535 }, live: None },
+    KnownKey { key: "enable-lan-visibility", kind: Kind::Bool, live: None },
+    KnownKey { key: "view-distance", kind: Kind::Int { min: 5, max: 96 }, live: None },
+    KnownKey { key: "tick-distance", kind: Kind::Int { min: 4, max: 12 }, live: None },
+    KnownKey { key: "player-idle-timeout", kind: Kind::Int { min: 0, max: 1_000_000 }, live: None },
+    KnownKey { key: "max-threads", kind: Kind::Int { min: 0, max: 1024 }, live: None },
+    KnownKey { key: "level-name", kind: Kind::Text, live: None },
+    KnownKey { key: "level-seed", kind: Kind::Text, live: None },
+    KnownKey {
+        key: "default-player-permission-level",
+        kind: Kind::Choice(&["visitor", "member", "operator"]),
+        live: None,
+    },
+    KnownKey { key: "texturepack-required", kind: Kind::Bool, live: None },
+    KnownKey { key: "content-log-file-enabled", kind: Kind::Bool, live: None },
+    KnownKey { key: "compression-threshold", kind: Kind::Int { min: 0, max: 65535 }, live: None },
+    KnownKey { key: "server-authoritative-movement", kind: Kind::Choice(&["client-auth", "server-auth", "server-auth-with-rewind"]), live: None },
+    KnownKey { key: "correct-player-movement", kind: Kind::Bool, live: None },
+    KnownKey { key: "server-authoritative-block-breaking", kind: Kind::Bool, live: None },
+    KnownKey { key: "chat-restriction", kind: Kind::Choice(&["None", "Dropped", "Disabled"]), live: None },
+    KnownKey { key: "disable-player-interaction", kind: Kind::Bool, live: None },
+    KnownKey { key: "emit-server-telemetry", kind: Kind::Bool, live: None },
+];
+
+/// `/config set` で受け付けない値。返信するときは言語ごとの文に置き換える
+#[derive(Debug, Clone)]
+pub enum InvalidValue {
+    /// 型の分からない設定で、ファイルにもない
+    UnknownKey,
+    MultiLine,
+    NotBool,
+    OutOfRange { min: i64, max: i64 },
+    NotChoice(&'static [&'static str]),
+}
+
+impl std::fmt::Display for InvalidValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InvalidValue::UnknownKey => write!(f, "unknown key"),
+            InvalidValue::MultiLine => write!(f, "value must be a single line"),
+            InvalidValue::NotBool => write!(f, "value must be true or false"),
+            InvalidValue::OutOfRange { min, max } => write!(f, "value must be an integer between {} and {}", min, max),
+            InvalidValue::NotChoice(choices) => write!(f, "value must be one of: {}", choices.join(", ")),
+        }
+    }
+}
+
+impl std::error::Error for InvalidValue {}
+
+/// 値を検証し、表記を揃えた値を返す
+fn validate(key: &str, value: &str) -> Result<String, InvalidValue> {
+    let value = value.trim();
+    if value.contains(['\r', '\n']) {
+        return Err(InvalidValue::MultiLine);
+    }
+    let Some(known) = KNOWN_KEYS.iter().find(|known| known.key == key) else {
+        return Ok(value.to_string());
+    };
+    match known.kind {
+        Kind::Bool => match value.to_lowercase().as_str() {
+            "true" | "on" | "yes" => Ok("true".to_string()),
+            "false" | "off" | "no" => Ok("false".to_string()),
+            _ => Err(InvalidValue::NotBool),
+        },
+        Kind::Int { min, max } => match value.parse::<i64>() {
+            Ok(n) if (min..=max).contains(&n) => Ok(n.to_string()),
+            _ => Err(InvalidValue::OutOfRange { min, max }),
+        },
+        Kind::Choice(choices) => choices
+            .iter()
+            .find(|choice| choice.eq_ignore_ascii_case(value))
+            .map(|choice| choice.to_string())
+            .ok_or(InvalidValue::NotChoice(choices)),
+        Kind::Text => Ok(value.to_string()),
+    }
+}
+
+/// `/config set` の結果
+#[derive(Debug, Clone)]
+pub struct ConfigChange {
+    pub previous: Option<String>,
+    pub value: String,
+    /// 再起動しないと反映されない
+    pub needs_restart: bool,
+}
+
+/// サーバーの server.properties
+pub struct ServerConfig {
+    path: PathBuf,
+    server_controller: Arc<ServerController>,
+}
+
+impl ServerConfig {
+    /// SERVER_PROPERTIES_PATH (既定はサーバーのフォルダの server.properties)
+    pub fn from_env(server_controller: Arc<ServerController>) -> Self {
+        let path = match env::var("SERVER_PROPERTIES_PATH") {
+            Ok(path) => PathBuf::from(path),
+            Err(_) => server_controller.server_dir().join("server.properties"),
+        };
+        Self { path, server_controller }
+    }
+
+    pub fn load(&self) -> Result<Properties, Box<dyn std::error::Error + Send + Sync>> {
+        let text = std::fs::read_to_string(&self.path).map_err(|e| format!("{}: {}", self.path.display(), e))?;
+        Ok(Properties::parse(&text))
+    }
+
+    /// 読み込まれるワールドのフォルダ名
+    pub fn level_name(&self) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let properties = self.load()?;
+        Ok(properties.get("level-name").filter(|name| !name.is_empty()).unwrap_or(DEFAULT_LEVEL_NAME).to_string())
+    }
+
+    pub fn save(&self, properties: &Properties) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        write_atomic(&self.path, properties.to_text().as_bytes())
+    }
+
+    /// 値を検証して書き込む。コマンドで反映できる設定で、サーバーが動いていればすぐに反映する。
+    /// 型の分からない設定は、ファイルにすでにあるものだけ変更できる (打ち間違いで新しいキーを作らないため)
+    pub fn set(&self, key: &str, value: &str) -> Result<ConfigChange, Box<dyn std::error::Error + Send + Sync>> {
+        let mut properties = self.load()?;
+        let previous = properties.get(key).map(str::to_string);
+        let known = KNOWN_KEYS.iter().find(|known| known.key == key);
+        if known.is_none() && previous.is_none() {
+            return Err(InvalidValue::UnknownKey.into());
+        }
+        let value = validate(key, value)?;
+        properties.set(key, &value);
+        self.save(&properties)?;
+
+        let mut needs_restart = previous.as_deref() != Some(value.as_str());
+        if let Some(live) = known.and_then(|known| known.live) {
+            if self.server_controller.is_running() {
+                match self.server_controller.send_command(&live(&value)) {
+                    Ok(()) => needs_restart = false,
+                    Err(e) => eprintln!("Failed to apply {} live: {}", key, e),
+                }
+            }
+        }
+        Ok(ConfigChange { previous, value, needs_restart })
+    }
+}
@@ -0,0 +1,118 @@
+use crate::allowlist::Allowlist;
+use crate::i18n::{tr, Language};
+use crate::links::LinkRegistry;
+use crate::playtime::PlaytimeTracker;
+use crate::server_controller::ServerController;
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use serenity::all::{CreateMessage, Http, UserId};
+use std::env;
+use std::sync::Arc;
+use std::time::Duration;
+
+const SCHEDULE_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+/// 指定できる日数の上限 (約100年)
+pub const MAX_PRUNE_DAYS: u32 = 36_500;
+
+#[derive(Debug, Clone)]
+pub struct InactiveEntry {
+    pub name: String,
+    pub last_seen: DateTime<Utc>,
+    pub user_id: Option<UserId>,
+}
+
+/// 長期間接続していないプレイヤーを allowlist から削除する
+pub struct Pruner {
+    allowlist: Allowlist,
+    playtime: Arc<PlaytimeTracker>,
+    links: Arc<LinkRegistry>,
+    server_controller: Arc<ServerController>,
+    schedule_days: Option<u32>,
+}
+
+impl Pruner {
+    /// PRUNE_INACTIVE_DAYS を指定すると1日1回自動で削除する
+    pub fn from_env(
+        playtime: Arc<PlaytimeTracker>,
+        links: Arc<LinkRegistry>,
+        server_controller: Arc<ServerController>,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let schedule_days = match env::var("PRUNE_INACTIVE_DAYS") {
+            Ok(days) => Some(
+                days.parse::<u32>()
+                    .ok()
+                    .filter(|days| (1..=MAX_PRUNE_DAYS).contains(days))
+                    .ok_or_else(|| format!("PRUNE_INACTIVE_DAYS must be between 1 and {}", MAX_PRUNE_DAYS))?,
+            ),
+            Err(_) => None,
+        };
+        Ok(Self {
+            allowlist: Allowlist::from_env(&server_controller),
+            playtime,
+            links,
+            server_controller,
+            schedule_days,
+        })
+    }
+
+    /// 最後の接続 (記録がなければ /server で登録した日時) から `days` 日以上経っているエントリ。
+    /// どちらも分からないエントリは対象にしない
+    pub async fn find_inactive(&self, days: u32) -> Result<Vec<InactiveEntry>, Box<dyn std::error::Error + Send + Sync>> {
+        let cutoff = ChronoDuration::try_days(days.into())
+            .and_then(|days| Utc::now().checked_sub_signed(days))
+            .ok_or_else(|| format!("too many days: {}", days))?;
+        let mut inactive = Vec::new();
+        for entry in self.allowlist.entries()? {
+            let link = self.links.user_for(&entry.name);
+            let last_seen = match self.playtime.last_seen(&entry.name).await {
+                Some(last_seen) => last_seen,
+                None => match &link {
+                    Some((_, link)) => link.linked_at,
+                    None => continue,
+                },
+            };
+            if last_seen < cutoff {
+                inactive.push(InactiveEntry {
+                    name: entry.name,
+                    last_seen,
+                    user_id: link.map(|(user_id, _)| user_id),
+                });
+            }
+        }
+        Ok(inactive)
+    }
+
+    /// 紐づいたユーザーにDMで知らせてから削除する
+    pub async fn prune(&self, http: &Http, days: u32) -> Result<Vec<InactiveEntry>, Box<dyn std::error::Error + Send + Sync>> {
+        let inactive = self.find_inactive(days).await?;
+        if inactive.is_empty() {
+            return Ok(inactive);
+        }
+        let lang = Language::from_env();
+        for entry in &inactive {
+            let Some(user_id) = entry.user_id else { continue };
+            let message = CreateMessage::new().content(tr!(lang, "prune-dm", gamertag = entry.name.as_str(), days = days));
+            if let Err(e) = user_id.direct_message(http, message).await {
+                eprintln!("Failed to notify {} about allowlist removal: {}", user_id, e);
+            }
+        }
+        let names: Vec<String> = inactive.iter().map(|entry| entry.name.clone()).collect();
+        self.allowlist.remove(&names, &self.server_controller)?;
+        println!("Pruned inactive allowlist entries: {}", names.join(", "));
+        Ok(inactive)
+    }
+
+    pub fn start_schedule(self: &Arc<Self>, http: Arc<Http>) {
+        let Some(days) = self.schedule_days else { return };
+        let pruner = Arc::clone(self);
+        tokio::spawn(async move {
+            // 起動直後には実行せず、最初は1周期後
+            let mut interval = tokio::time::interval_at(tokio::time::Instant::now() + SCHEDULE_INTERVAL, SCHEDULE_INTERVAL);
+            loop {
+                interval.tick().await;
+                if let Err(e) = pruner.prune(&http, days).await {
+                    eprintln!("Scheduled allowlist prune failed: {}", e);
+                }
+            }
+        });
+    }
+}
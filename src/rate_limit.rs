@@ -0,0 +1,164 @@
+use crate::duration::parse_duration;
+use serenity::all::UserId;
+use std::collections::HashMap;
+use std::env;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// これ以上ユーザーごとのバケツが増えたら、満タンに戻ったものを捨てる
+const MAX_TRACKED_BUCKETS: usize = 1000;
+
+/// `回数/期間` (例: "5/10s")。期間内に `回数` までまとめて使え、その後は一定の間隔で回復する
+#[derive(Debug, Clone, Copy)]
+struct Limit {
+    capacity: f64,
+    per_second: f64,
+}
+
+impl Limit {
+    fn parse(text: &str) -> Result<Self, String> {
+        let (count, period) = text.split_once('/').ok_or_else(|| format!("expected <count>/<period>, got '{}'", text))?;
+        let count = count.trim().parse::<u32>().ok().filter(|count| *count > 0).ok_or_else(|| format!("invalid count in '{}'", text))?;
+        let period = parse_duration(period.trim())?;
+        if period.is_zero() {
+            return Err(format!("period must not be zero in '{}'", text));
+        }
+        Ok(Self {
+            capacity: count as f64,
+            per_second: count as f64 / period.as_secs_f64(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    fn full(limit: &Limit) -> Self {
+        Self { tokens: limit.capacity, updated: Instant::now() }
+    }
+
+    fn refill(&mut self, limit: &Limit, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * limit.per_second).min(limit.capacity);
+        self.updated = now;
+    }
+
+    /// 1回分がたまるまでの時間
+    fn wait(&self, limit: &Limit) -> Duration {
+        if self.tokens >= 1.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64((1.0 - self.tokens) / limit.per_second)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Key {
+    Global,
+    User(UserId),
+    Command(UserId, String),
+}
+
+/// コマンドの実行回数をトークンバケツで制限する (全体・ユーザーごと・ユーザーとコマンドごと)
+pub struct RateLimiter {
+    global: Option<Limit>,
+    user: Option<Limit>,
+    commands: HashMap<String, Limit>,
+    buckets: Mutex<HashMap<Key, Bucket>>,
+}
+
+impl RateLimiter {
+    /// RATE_LIMIT_GLOBAL (既定 30/10s)、RATE_LIMIT_USER (既定 5/10s)、
+    /// RATE_LIMIT_COMMANDS (既定 "server=2/1m,restart=1/1m")。"off" で無効にできる
+    pub fn from_env() -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let limit = |var: &str, default: &str| -> Result<Option<Limit>, String> {
+            let value = env::var(var).unwrap_or_else(|_| default.to_string());
+            if value.trim().is_empty() || value.trim().eq_ignore_ascii_case("off") {
+                return Ok(None);
+            }
+            Limit::parse(&value).map(Some).map_err(|e| format!("{}: {}", var, e))
+        };
+        let commands_value = env::var("RATE_LIMIT_COMMANDS").unwrap_or_else(|_| "server=2/1m,restart=1/1m".to_string());
+        let mut commands = HashMap::new();
+        if !commands_value.trim().eq_ignore_ascii_case("off") {
+            for entry in commands_value.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+                let (command, value) = entry
+                    .split_once('=')
+                    .ok_or_else(|| format!("RATE_LIMIT_COMMANDS: expected <command>=<count>/<period>, got '{}'", entry))?;
+                let value = Limit::parse(value).map_err(|e| format!("RATE_LIMIT_COMMANDS: {}", e))?;
+                commands.insert(command.trim().to_lowercase(), value);
+            }
+        }
+        Ok(Self {
+            global: limit("RATE_LIMIT_GLOBAL", "30/10s")?,
+            user: limit("RATE_LIMIT_USER", "5/10s")?,
+            commands,
+            buckets: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// 実行してよければ1回分を消費する。制限に掛かった場合は使えるようになるまでの時間を返す。
+    /// `command` が None なら全体とユーザーごとの制限だけを確認する
+    pub fn check(&self, user_id: UserId, command: Option<&str>) -> Result<(), Duration> {
+        let mut limits = Vec::with_capacity(3);
+        if let Some(limit) = self.global {
+            limits.push((Key::Global, limit));
+        }
+        if let Some(limit) = self.user {
+            limits.push((Key::User(user_id), limit));
+        }
+        limits.extend(command.and_then(|command| self.command_limit(user_id, command)));
+        self.consume(&limits)
+    }
+
+    /// コマンドごとの制限だけを確認する。全体とユーザーごとの分は `check` ですでに消費したとき
+    /// (/server のフォームの送信など) に使う
+    pub fn check_command(&self, user_id: UserId, command: &str) -> Result<(), Duration> {
+        let limits: Vec<(Key, Limit)> = self.command_limit(user_id, command).into_iter().collect();
+        self.consume(&limits)
+    }
+
+    fn command_limit(&self, user_id: UserId, command: &str) -> Option<(Key, Limit)> {
+        let (command, limit) = self.commands.get_key_value(command)?;
+        Some((Key::Command(user_id, command.clone()), *limit))
+    }
+
+    fn consume(&self, limits: &[(Key, Limit)]) -> Result<(), Duration> {
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap();
+        if buckets.len() > MAX_TRACKED_BUCKETS {
+            buckets.retain(|key, bucket| {
+                let limit = match key {
+                    Key::Global => self.global,
+                    Key::User(_) => self.user,
+                    Key::Command(_, command) => self.commands.get(command).copied(),
+                };
+                limit.is_some_and(|limit| {
+                    bucket.refill(&limit, now);
+                    bucket.tokens < limit.capacity
+                })
+            });
+        }
+        // どれか1つでも足りなければ、どのバケツからも消費しない
+        let mut wait = Duration::ZERO;
+        for (key, limit) in limits {
+            let bucket = buckets.entry(key.clone()).or_insert_with(|| Bucket::full(limit));
+            bucket.refill(limit, now);
+            wait = wait.max(bucket.wait(limit));
+        }
+        if !wait.is_zero() {
+            return Err(wait);
+        }
+        for (key, _) in limits {
+            if let Some(bucket) = buckets.get_mut(key) {
+                bucket.tokens -= 1.0;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,156 @@
+use crate::audit::{AuditEntry, AuditLog};
+use crate::duration::{format_duration, parse_duration};
+use crate::i18n::{tr, Language};
+use crate::restart::RestartScheduler;
+use crate::server_controller::{ResourceUsage, ServerController};
+use crate::size::{format_size, parse_size};
+use serenity::all::{ChannelId, CreateAllowedMentions, CreateMessage, Http};
+use std::collections::BTreeMap;
+use std::env;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemoryAction {
+    /// 通知だけする
+    Alert,
+    /// 通知して再起動を予約する
+    Restart,
+}
+
+/// bedrock_server の CPU とメモリを定期的に計測し、メモリの上限を超えたら通知・再起動する
+pub struct ResourceMonitor {
+    server_controller: Arc<ServerController>,
+    restart: Arc<RestartScheduler>,
+    audit: Arc<AuditLog>,
+    interval: Duration,
+    memory_limit: Option<u64>,
+    action: MemoryAction,
+    restart_delay: Duration,
+    alert_channel: Option<ChannelId>,
+    // 上限を超えている間は1回だけ通知する
+    over_limit: AtomicBool,
+}
+
+impl ResourceMonitor {
+    /// RESOURCE_SAMPLE_INTERVAL (既定 30s) ごとに計測する。MEMORY_LIMIT (例: 4G) を超えたら
+    /// MEMORY_ALERT_CHANNEL_ID と監査ログに通知し、MEMORY_LIMIT_ACTION=restart なら
+    /// MEMORY_RESTART_DELAY (既定 5m) 後の再起動を予約する
+    pub fn from_env(
+        server_controller: Arc<ServerController>,
+        restart: Arc<RestartScheduler>,
+        audit: Arc<AuditLog>,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let duration = |var: &str, default: &str| -> Result<Duration, String> {
+            parse_duration(&env::var(var).unwrap_or_else(|_| default.to_string())).map_err(|e| format!("{}: {}", var, e))
+        };
+        let memory_limit = match env::var("MEMORY_LIMIT") {
+            Ok(limit) if !limit.trim().is_empty() => Some(parse_size(&limit).map_err(|e| format!("MEMORY_LIMIT: {}", e))?),
+            _ => None,
+        };
+        let action = match env::var("MEMORY_LIMIT_ACTION").unwrap_or_default().trim().to_lowercase().as_str() {
+            "" | "alert" => MemoryAction::Alert,
+            "restart" => MemoryAction::Restart,
+            other => return Err(format!("MEMORY_LIMIT_ACTION must be alert or restart, got '{}'", other).into()),
+        };
+        let alert_channel = match env::var("MEMORY_ALERT_CHANNEL_ID") {
+            Ok(id) => Some(ChannelId::new(
+                id.parse::<u64>().map_err(|_| "MEMORY_ALERT_CHANNEL_ID must be a valid u64")?,
+            )),
+            Err(_) => None,
+        };
+        Ok(Self {
+            server_controller,
+            restart,
+            audit,
+            interval: duration("RESOURCE_SAMPLE_INTERVAL", "30s")?,
+            memory_limit,
+            action,
+            restart_delay: duration("MEMORY_RESTART_DELAY", "5m")?,
+            alert_channel,
+            over_limit: AtomicBool::new(false),
+        })
+    }
+
+    /// CPU 使用率の表示 (計測が1回だけのときは "-")
+    pub fn format_cpu(usage: &ResourceUsage) -> String {
+        usage.cpu_percent.map_or_else(|| "-".to_string(), |cpu| format!("{:.1}%", cpu))
+    }
+
+    /// メモリ使用量の表示。上限があれば `使用量 / 上限`
+    pub fn format_memory(&self, usage: &ResourceUsage) -> String {
+        match self.memory_limit {
+            Some(limit) => format!("{} / {}", format_size(usage.rss), format_size(limit)),
+            None => format_size(usage.rss),
+        }
+    }
+
+    pub fn start(self: &Arc<Self>, http: Arc<Http>) {
+        let monitor = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(monitor.interval);
+            loop {
+                interval.tick().await;
+                // /proc を読むのでブロックしてよいスレッドで計測する
+                let server_controller = Arc::clone(&monitor.server_controller);
+                let usage = match tokio::task::spawn_blocking(move || server_controller.sample_resources()).await {
+                    Ok(usage) => usage,
+                    Err(e) => {
+                        eprintln!("Failed to sample resource usage: {}", e);
+                        continue;
+                    }
+                };
+                let Some(limit) = monitor.memory_limit else { continue };
+                match usage {
+                    Some(usage) if usage.rss > limit => {
+                        if !monitor.over_limit.swap(true, Ordering::SeqCst) {
+                            monitor.alert(&http, usage.rss, limit).await;
+                        }
+                    }
+                    // 再起動などで下回ったら、次に超えたときにまた通知する
+                    _ => monitor.over_limit.store(false, Ordering::SeqCst),
+                }
+            }
+        });
+    }
+
+    async fn alert(&self, http: &Http, rss: u64, limit: u64) {
+        let lang = Language::from_env();
+        eprintln!("bedrock_server is using {} of memory (limit {})", format_size(rss), format_size(limit));
+        let mut msg = tr!(lang, "memory-alert", memory = format_size(rss), limit = format_size(limit));
+        let mut result = Ok("alerted".to_string());
+        if self.action == MemoryAction::Restart {
+            match self.restart.pending() {
+                // すでに予約されている再起動を早めたり遅らせたりしない
+                Some(at) => {
+                    msg.push('\n');
+                    msg.push_str(&tr!(lang, "memory-restart-pending", time = format!("<t:{}:R>", at.timestamp())));
+                }
+                None => match self.restart.schedule_in(self.restart_delay) {
+                    Ok(at) => {
+                        println!("Restart scheduled in {} because of memory usage", format_duration(self.restart_delay));
+                        msg.push('\n');
+                        msg.push_str(&tr!(lang, "memory-restart-scheduled", time = format!("<t:{}:R>", at.timestamp())));
+                        result = Ok(format!("restart scheduled at {}", at.to_rfc3339()));
+                    }
+                    Err(e) => {
+                        eprintln!("Failed to schedule a restart for memory usage: {}", e);
+                        result = Err(e.to_string());
+                    }
+                },
+            }
+        }
+        let arguments = BTreeMap::from([
+            ("memory".to_string(), format_size(rss)),
+            ("limit".to_string(), format_size(limit)),
+        ]);
+        self.audit.record(AuditEntry::new(None, "memory-limit", arguments, result));
+        if let Some(channel_id) = self.alert_channel {
+            let builder = CreateMessage::new().content(msg).allowed_mentions(CreateAllowedMentions::new());
+            if let Err(e) = channel_id.send_message(http, builder).await {
+                eprintln!("Failed to send memory alert: {}", e);
+            }
+        }
+    }
+}
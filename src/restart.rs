@@ -0,0 +1,137 @@
+use crate::i18n::{tr, Language};
+use crate::lifecycle::Lifecycle;
+use crate::maintenance::Maintenance;
+use crate::schedule::{next_run, schedule_from_env, sleep_until};
+use crate::server_controller::ServerController;
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use cron::Schedule;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+/// 再起動前にゲーム内で告知するタイミング (再起動までの秒数)
+const WARNINGS: [u64; 4] = [600, 300, 60, 10];
+/// /restart delay で予約できる最長の時間
+pub const MAX_RESTART_DELAY: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// 予約された再起動とゲーム内のカウントダウン
+pub struct RestartScheduler {
+    server_controller: Arc<ServerController>,
+    lifecycle: Arc<Lifecycle>,
+    maintenance: Arc<Maintenance>,
+    schedule: Option<Schedule>,
+    pending: Mutex<Option<(DateTime<Utc>, JoinHandle<()>)>>,
+}
+
+impl RestartScheduler {
+    /// RESTART_SCHEDULE に cron 形式で定期再起動の時刻を指定できる (例: "0 0 4 * * *")
+    pub fn from_env(
+        server_controller: Arc<ServerController>,
+        lifecycle: Arc<Lifecycle>,
+        maintenance: Arc<Maintenance>,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Self {
+            server_controller,
+            lifecycle,
+            maintenance,
+            schedule: schedule_from_env("RESTART_SCHEDULE")?,
+            pending: Mutex::new(None),
+        })
+    }
+
+    /// 予約中の再起動の時刻
+    pub fn pending(&self) -> Option<DateTime<Utc>> {
+        self.pending.lock().unwrap().as_ref().map(|(at, _)| *at)
+    }
+
+    /// `delay` 後に再起動する。すでに予約があれば置き換える。MAX_RESTART_DELAY より長ければエラー
+    pub fn schedule_in(self: &Arc<Self>, delay: Duration) -> Result<DateTime<Utc>, Box<dyn std::error::Error + Send + Sync>> {
+        if delay > MAX_RESTART_DELAY {
+            return Err(format!("restart delay must be at most {} minutes", MAX_RESTART_DELAY.as_secs() / 60).into());
+        }
+        let at = Utc::now() + ChronoDuration::from_std(delay)?;
+        self.schedule_at(at);
+        Ok(at)
+    }
+
+    fn schedule_at(self: &Arc<Self>, at: DateTime<Utc>) {
+        // タスクが予約の登録より先に終わらないよう、ロックを持ったまま起動する
+        let mut pending = self.pending.lock().unwrap();
+        let scheduler = Arc::clone(self);
+        let handle = tokio::spawn(async move {
+            scheduler.countdown(at).await;
+            scheduler.pending.lock().unwrap().take();
+            // メンテナンスで止めているサーバーを起動してしまわないようにする
+            if scheduler.maintenance.keeps_stopped() {
+                println!("Skipping scheduled restart during maintenance.");
+                return;
+            }
+            if let Err(e) = scheduler.lifecycle.restart().await {
+                eprintln!("Scheduled restart aborted: {}", e);
+                let message = tr!(Language::from_env(), "restart-aborted-announce");
+                if let Err(e) = scheduler.server_controller.send_command(&format!("say {}", message)) {
+                    eprintln!("Failed to announce restart: {}", e);
+                }
+            }
+        });
+        if let Some((_, previous)) = pending.replace((at, handle)) {
+            previous.abort();
+        }
+        println!("Server restart scheduled at {}", at);
+    }
+
+    /// 予約を取り消す。予約がなければ false
+    pub fn cancel(&self) -> bool {
+        match self.pending.lock().unwrap().take() {
+            Some((_, handle)) => {
+                handle.abort();
+                println!("Scheduled server restart cancelled.");
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 予約を取り消してすぐに再起動する。必須のフックが失敗した場合は再起動せずにエラーを返す
+    pub async fn restart_now(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.cancel();
+        self.lifecycle.restart().await
+    }
+
+    async fn countdown(&self, at: DateTime<Utc>) {
+        let lang = Language::from_env();
+        for secs in WARNINGS {
+            let warn_at = at - ChronoDuration::seconds(secs as i64);
+            if warn_at < Utc::now() {
+                continue;
+            }
+            sleep_until(warn_at).await;
+            let message = if secs >= 60 {
+                tr!(lang, "restart-warning-minutes", minutes = secs / 60)
+            } else {
+                tr!(lang, "restart-warning-seconds", seconds = secs)
+            };
+            if let Err(e) = self.server_controller.send_command(&format!("say {}", message)) {
+                eprintln!("Failed to announce restart: {}", e);
+            }
+        }
+        sleep_until(at).await;
+    }
+
+    /// RESTART_SCHEDULE の時刻に再起動する。告知が間に合うよう最初の告知の少し前に予約する
+    pub fn start_schedule(self: &Arc<Self>) {
+        let Some(schedule) = self.schedule.clone() else { return };
+        let scheduler = Arc::clone(self);
+        tokio::spawn(async move {
+            let lead = ChronoDuration::seconds(WARNINGS[0] as i64 + 60);
+            let mut after = Utc::now();
+            while let Some(next) = next_run(&schedule, after) {
+                sleep_until(next - lead).await;
+                if scheduler.pending().is_none() && !scheduler.maintenance.keeps_stopped() {
+                    scheduler.schedule_at(next);
+                }
+                after = next;
+            }
+        });
+    }
+}
@@ -0,0 +1,30 @@
+use chrono::{DateTime, Local, Utc};
+use cron::Schedule;
+use std::env;
+use std::str::FromStr;
+
+/// cron 形式 (秒 分 時 日 月 曜日 [年]) のスケジュールを環境変数から読み込む。未設定なら None
+pub fn schedule_from_env(var: &str) -> Result<Option<Schedule>, Box<dyn std::error::Error + Send + Sync>> {
+    match env::var(var) {
+        Ok(expr) if !expr.trim().is_empty() => {
+            let schedule = Schedule::from_str(expr.trim()).map_err(|e| format!("{}: {}", var, e))?;
+            Ok(Some(schedule))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// ローカル時刻で次に実行する時刻
+pub fn next_run(schedule: &Schedule, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    schedule
+        .after(&after.with_timezone(&Local))
+        .next()
+        .map(|next| next.with_timezone(&Utc))
+}
+
+/// `at` まで待つ (過去の時刻ならすぐに戻る)
+pub async fn sleep_until(at: DateTime<Utc>) {
+    if let Ok(remaining) = (at - Utc::now()).to_std() {
+        tokio::time::sleep(remaining).await;
+    }
+}
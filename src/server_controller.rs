@@ -1,15 +1,60 @@
 use std::process::{Stdio, Child, ChildStdin};
 use std::sync::{Arc, Mutex};
-use std::io::Write;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::time::{Duration, Instant};
 #[cfg(windows)]
 use std::os::windows::process::CommandExt;
-use tokio::time::{sleep, Duration};
+use tokio::sync::{broadcast, watch};
+
+const OUTPUT_CHANNEL_CAPACITY: usize = 1024;
+/// send_command_capture で応答を待つ最大時間
+const CAPTURE_TIMEOUT: Duration = Duration::from_secs(5);
+/// 起動が完了したときに bedrock_server が出力する行
+pub const READY_LINE: &str = "Server started.";
+/// stop のあと、この時間内に終了しなければプロセスを強制終了する
+const STOP_TIMEOUT: Duration = Duration::from_secs(60);
+/// /proc の CPU 時間の単位 (USER_HZ。Linux ではほぼ常に 100)
+const CLOCK_TICKS_PER_SEC: f64 = 100.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServerState {
+    Stopped,
+    /// プロセスは起動したが、ワールドの読み込みが終わっていない
+    Starting,
+    Running,
+    Stopping,
+}
+
+/// bedrock_server プロセスの CPU 使用率とメモリ使用量
+#[derive(Debug, Clone, Copy)]
+pub struct ResourceUsage {
+    /// 前回の計測からの CPU 使用率 (1コア = 100%)。初回は None
+    pub cpu_percent: Option<f64>,
+    /// 常駐メモリ (RSS) のバイト数
+    pub rss: u64,
+}
+
+/// CPU 使用率の計算に使う前回の計測
+#[derive(Debug, Clone, Copy)]
+struct CpuSample {
+    pid: u32,
+    ticks: u64,
+    at: Instant,
+}
 
 #[derive(Clone)]
 pub struct ServerController {
     process: Arc<Mutex<Option<Child>>>,
+    // start と stop を同時に実行しない (stop は終了を待つ間 process のロックを持たない)
+    transition: Arc<Mutex<()>>,
     stdin: Arc<Mutex<Option<ChildStdin>>>,
+    started_at: Arc<Mutex<Option<Instant>>>,
+    output: broadcast::Sender<String>,
+    state: watch::Sender<ServerState>,
+    cpu_sample: Arc<Mutex<Option<CpuSample>>>,
+    usage: Arc<Mutex<Option<ResourceUsage>>>,
     server_path: String,
 }
 
@@ -17,21 +62,23 @@ impl ServerController {
     pub fn new(server_path: String) -> Self {
         Self {
             process: Arc::new(Mutex::new(None)),
+            transition: Arc::new(Mutex::new(())),
             stdin: Arc::new(Mutex::new(None)),
+            started_at: Arc::new(Mutex::new(None)),
+            output: broadcast::channel(OUTPUT_CHANNEL_CAPACITY).0,
+            state: watch::channel(ServerState::Stopped).0,
+            cpu_sample: Arc::new(Mutex::new(None)),
+            usage: Arc::new(Mutex::new(None)),
             server_path,
         }
     }
 
-    pub fn start(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        let mut process_guard = self.process.lock().unwrap();
-        if process_guard.is_some() {
-            println!("Server is already running.");
-            return Ok(());
-        }
-        let path = std::path::Path::new(&self.server_path);
-        let (work_dir, exe_path) = if path.is_file() {
+    /// (作業ディレクトリ, 実行ファイル)。SERVER_PATH は実行ファイルでもディレクトリでもよい
+    fn paths(&self) -> (PathBuf, PathBuf) {
+        let path = Path::new(&self.server_path);
+        if path.is_file() {
             (
-                path.parent().unwrap_or(std::path::Path::new(".")).to_path_buf(),
+                path.parent().unwrap_or(Path::new(".")).to_path_buf(),
                 path.to_path_buf()
             )
         } else {
@@ -39,47 +86,131 @@ impl ServerController {
                 path.to_path_buf(),
                 path.join("bedrock_server.exe")
             )
-        };
+        }
+    }
+
+    /// bedrock_server の作業ディレクトリ (server.properties や worlds のある場所)
+    pub fn server_dir(&self) -> PathBuf {
+        self.paths().0
+    }
+
+    /// ワールドを置くフォルダ。WORLDS_PATH (既定はサーバーの worlds フォルダ)
+    pub fn worlds_dir(&self) -> PathBuf {
+        match std::env::var("WORLDS_PATH") {
+            Ok(path) => PathBuf::from(path),
+            Err(_) => self.server_dir().join("worlds"),
+        }
+    }
+
+    pub fn start(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let _transition = self.transition.lock().unwrap();
+        let mut process_guard = self.process.lock().unwrap();
+        // クラッシュして終了したプロセスは片付けて起動し直す
+        if let Some(child) = process_guard.as_mut() {
+            if matches!(child.try_wait(), Ok(None)) {
+                println!("Server is already running.");
+                return Ok(());
+            }
+            *process_guard = None;
+        }
+        let (work_dir, exe_path) = self.paths();
         println!("Starting {:?} from {:?}", exe_path, work_dir);
         
         let mut cmd = Command::new(&exe_path);
         cmd.current_dir(&work_dir)
             .stdin(Stdio::piped())
-            .stdout(Stdio::inherit())
+            .stdout(Stdio::piped())
             .stderr(Stdio::inherit());
 
         #[cfg(windows)]
         cmd.creation_flags(0x00000200);
         let mut child = cmd.spawn()?;
         let stdin = child.stdin.take().ok_or("Failed to open stdin")?;
+        let stdout = child.stdout.take().ok_or("Failed to open stdout")?;
+        let output = self.output.clone();
+        let state = self.state.clone();
+        // 読み込みスレッドが終了を知らせる前に Starting にしておく
+        self.state.send_replace(ServerState::Starting);
+        std::thread::spawn(move || {
+            // 出力が UTF-8 とは限らないので lines() ではなくバイト単位で読む
+            let mut reader = BufReader::new(stdout);
+            let mut buf = Vec::new();
+            loop {
+                buf.clear();
+                match reader.read_until(b'\n', &mut buf) {
+                    Ok(0) | Err(_) => break,
+                    Ok(_) => {}
+                }
+                let line = String::from_utf8_lossy(&buf).trim_end_matches(['\r', '\n']).to_string();
+                println!("{}", line);
+                if line.contains(READY_LINE) {
+                    state.send_if_modified(|state| {
+                        let ready = *state == ServerState::Starting;
+                        if ready {
+                            *state = ServerState::Running;
+                        }
+                        ready
+                    });
+                }
+                // 購読者がいない場合はエラーになるが無視してよい
+                let _ = output.send(line);
+            }
+            // 標準出力が閉じた = プロセスが終了した (クラッシュを含む)
+            state.send_replace(ServerState::Stopped);
+        });
         *process_guard = Some(child);
         *self.stdin.lock().unwrap() = Some(stdin);
+        *self.started_at.lock().unwrap() = Some(Instant::now());
         println!("Bedrock Server started successfully.");
         Ok(())
     }
 
+    /// `stop` を送って終了を待つ。STOP_TIMEOUT を過ぎても終了しなければ強制終了する。
+    /// 待っている間も is_running などがブロックしないよう、プロセスはロックから取り出しておく
     pub fn stop(&self) {
+        let _transition = self.transition.lock().unwrap();
         println!("Stopping server...");
+        if self.process.lock().unwrap().is_some() {
+            self.state.send_replace(ServerState::Stopping);
+        }
         if let Err(e) = self.send_command("stop") {
             eprintln!("Failed to send stop command: {}", e);
         }
-        let mut process_guard = self.process.lock().unwrap();
-        if let Some(mut child) = process_guard.take() {
-            match child.wait() {
-                Ok(status) => println!("Server exited with status: {}", status),
-                Err(e) => eprintln!("Error waiting for server exit: {}", e),
+        let child = self.process.lock().unwrap().take();
+        if let Some(mut child) = child {
+            let deadline = Instant::now() + STOP_TIMEOUT;
+            loop {
+                match child.try_wait() {
+                    Ok(Some(status)) => {
+                        println!("Server exited with status: {}", status);
+                        break;
+                    }
+                    Ok(None) if Instant::now() < deadline => std::thread::sleep(Duration::from_millis(200)),
+                    Ok(None) => {
+                        eprintln!("Server did not exit within {}s; killing it", STOP_TIMEOUT.as_secs());
+                        if let Err(e) = child.kill() {
+                            eprintln!("Failed to kill server: {}", e);
+                        }
+                        let _ = child.wait();
+                        break;
+                    }
+                    Err(e) => {
+                        eprintln!("Error waiting for server exit: {}", e);
+                        break;
+                    }
+                }
             }
         }
         *self.stdin.lock().unwrap() = None;
+        *self.started_at.lock().unwrap() = None;
+        *self.usage.lock().unwrap() = None;
+        self.state.send_replace(ServerState::Stopped);
         println!("Server stopped.");
     }
-    pub fn restart(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        self.stop();
-        std::thread::sleep(std::time::Duration::from_secs(2));
-        self.start()
-    }
-
     pub fn send_command(&self, cmd: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if cmd.contains(['\r', '\n']) {
+            return Err("command must be a single line".into());
+        }
         let mut stdin_guard = self.stdin.lock().unwrap();
         if let Some(stdin) = stdin_guard.as_mut() {
             writeln!(stdin, "{}", cmd)?;
@@ -89,6 +220,36 @@ impl ServerController {
             Err("Server stdin is not available (server not running?)".into())
         }
     }
+
+    /// プレイヤーをキックする。理由は引用符で囲み、`"` と `\` をエスケープして送る
+    pub fn kick(&self, gamertag: &str, reason: Option<&str>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let command = match reason {
+            Some(reason) => format!("kick \"{}\" \"{}\"", gamertag, reason.replace('\\', "\\\\").replace('"', "\\\"")),
+            None => format!("kick \"{}\"", gamertag),
+        };
+        self.send_command(&command)
+    }
+
+    /// コマンドを送り、出力が `quiet` の間途切れるまでに出力された行を集める
+    pub async fn send_command_capture(
+        &self,
+        cmd: &str,
+        quiet: Duration,
+    ) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut output = self.subscribe_output();
+        self.send_command(cmd)?;
+        let deadline = tokio::time::Instant::now() + CAPTURE_TIMEOUT;
+        let mut lines = Vec::new();
+        loop {
+            let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+            match tokio::time::timeout(quiet.min(remaining), output.recv()).await {
+                Ok(Ok(line)) => lines.push(line),
+                Ok(Err(broadcast::error::RecvError::Lagged(_))) => continue,
+                _ => break,
+            }
+        }
+        Ok(lines)
+    }
     
     pub fn is_running(&self) -> bool {
         let mut process_guard = self.process.lock().unwrap();
@@ -102,4 +263,81 @@ impl ServerController {
             false
         }
     }
+
+    /// bedrock_server の標準出力を1行ずつ受け取る
+    pub fn subscribe_output(&self) -> broadcast::Receiver<String> {
+        self.output.subscribe()
+    }
+
+    /// サーバーの起動・停止を監視する
+    pub fn subscribe_state(&self) -> watch::Receiver<ServerState> {
+        self.state.subscribe()
+    }
+
+    pub fn state(&self) -> ServerState {
+        *self.state.borrow()
+    }
+
+    /// 動いているサーバープロセスのID
+    pub fn pid(&self) -> Option<u32> {
+        let mut process_guard = self.process.lock().unwrap();
+        let child = process_guard.as_mut()?;
+        match child.try_wait() {
+            Ok(None) => Some(child.id()),
+            _ => None,
+        }
+    }
+
+    /// /proc からプロセスの CPU とメモリを計測して記録する。Linux 以外やサーバーが止まっているときは None
+    pub fn sample_resources(&self) -> Option<ResourceUsage> {
+        let usage = self.pid().and_then(|pid| {
+            let (ticks, rss) = read_proc_stats(pid)?;
+            let now = Instant::now();
+            let mut sample = self.cpu_sample.lock().unwrap();
+            let cpu_percent = sample
+                .filter(|sample| sample.pid == pid && ticks >= sample.ticks)
+                .map(|sample| (ticks - sample.ticks, now.duration_since(sample.at).as_secs_f64()))
+                .filter(|(_, elapsed)| *elapsed > 0.0)
+                .map(|(ticks, elapsed)| ticks as f64 / CLOCK_TICKS_PER_SEC / elapsed * 100.0);
+            *sample = Some(CpuSample { pid, ticks, at: now });
+            Some(ResourceUsage { cpu_percent, rss })
+        });
+        *self.usage.lock().unwrap() = usage;
+        usage
+    }
+
+    /// 最後に計測した CPU とメモリ
+    pub fn resource_usage(&self) -> Option<ResourceUsage> {
+        *self.usage.lock().unwrap()
+    }
+
+    /// サーバープロセスを起動してからの経過時間
+    pub fn uptime(&self) -> Option<Duration> {
+        self.started_at.lock().unwrap().map(|started| started.elapsed())
+    }
+}
+
+/// /proc/<pid>/stat の utime + stime (クロック刻み) と /proc/<pid>/status の VmRSS (バイト)
+#[cfg(target_os = "linux")]
+fn read_proc_stats(pid: u32) -> Option<(u64, u64)> {
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    // 2番目の項目 (実行ファイル名) は空白や括弧を含むことがあるので、最後の ')' より後ろを読む
+    let fields: Vec<&str> = stat[stat.rfind(')')? + 1..].split_whitespace().collect();
+    let utime: u64 = fields.get(11)?.parse().ok()?;
+    let stime: u64 = fields.get(12)?.parse().ok()?;
+    let status = std::fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+    let rss_kib: u64 = status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmRSS:"))?
+        .trim()
+        .trim_end_matches("kB")
+        .trim()
+        .parse()
+        .ok()?;
+    Some((utime + stime, rss_kib * 1024))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_proc_stats(_pid: u32) -> Option<(u64, u64)> {
+    None
 }
@@ -0,0 +1,47 @@
+use crate::addons::Addons;
+use crate::audit::AuditLog;
+use crate::auth::Admins;
+use crate::backup::BackupManager;
+use crate::bans::BanList;
+use crate::confirm::Confirmations;
+use crate::guests::GuestPasses;
+use crate::lifecycle::Lifecycle;
+use crate::links::LinkRegistry;
+use crate::maintenance::Maintenance;
+use crate::permissions::Permissions;
+use crate::players::PlayerTracker;
+use crate::playtime::PlaytimeTracker;
+use crate::properties::ServerConfig;
+use crate::prune::Pruner;
+use crate::rate_limit::RateLimiter;
+use crate::resources::ResourceMonitor;
+use crate::restart::RestartScheduler;
+use crate::server_controller::ServerController;
+use crate::updater::Updater;
+use crate::worlds::Worlds;
+use std::sync::Arc;
+
+/// スラッシュコマンドなどから参照する共有の状態
+pub struct AppState {
+    pub server_controller: Arc<ServerController>,
+    pub players: Arc<PlayerTracker>,
+    pub playtime: Arc<PlaytimeTracker>,
+    pub links: Arc<LinkRegistry>,
+    pub pruner: Arc<Pruner>,
+    pub guests: Arc<GuestPasses>,
+    pub lifecycle: Arc<Lifecycle>,
+    pub restart: Arc<RestartScheduler>,
+    pub resources: Arc<ResourceMonitor>,
+    pub updater: Arc<Updater>,
+    pub backups: Arc<BackupManager>,
+    pub config: Arc<ServerConfig>,
+    pub addons: Arc<Addons>,
+    pub worlds: Arc<Worlds>,
+    pub bans: Arc<BanList>,
+    pub audit: Arc<AuditLog>,
+    pub rate_limiter: RateLimiter,
+    pub confirmations: Confirmations,
+    pub permissions: Arc<Permissions>,
+    pub maintenance: Arc<Maintenance>,
+    pub admins: Admins,
+}
@@ -6,24 +6,91 @@ use std::{sync::Arc, time::{SystemTime, UNIX_EPOCH}};
 use tokio::net::UdpSocket;
 use tokio::sync::RwLock;
 use tokio::time::{sleep, Duration, timeout};
+use crate::duration::format_duration;
+use crate::i18n::{tr, Language};
+use crate::resources::ResourceMonitor;
+use crate::state::AppState;
+use crate::store::{load_json, save_json};
+use crate::template::Template;
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
 use std::env;
+use std::path::PathBuf;
+use std::time::Instant;
+
+const ONLINE_PLACEHOLDERS: &[&str] = &[
+    "ip", "port", "time", "restart", "players", "max", "player_names", "motd", "version", "uptime", "latency", "cpu",
+    "memory",
+];
+const OFFLINE_PLACEHOLDERS: &[&str] = &["ip", "port", "time", "restart"];
+const MAINTENANCE_PLACEHOLDERS: &[&str] = &["ip", "port", "time", "reason", "eta"];
+
+/// ユーザー定義のステータスメッセージ。未設定の方は組み込みのメッセージを使う
+#[derive(Default)]
+pub struct StatusTemplates {
+    online: Option<Template>,
+    offline: Option<Template>,
+    maintenance: Option<Template>,
+}
+
+impl StatusTemplates {
+    /// STATUS_TEMPLATE_ONLINE / STATUS_TEMPLATE_OFFLINE / STATUS_TEMPLATE_MAINTENANCE にテンプレートファイルのパスを指定する
+    pub fn from_env() -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Self {
+            online: Self::load("STATUS_TEMPLATE_ONLINE", ONLINE_PLACEHOLDERS)?,
+            offline: Self::load("STATUS_TEMPLATE_OFFLINE", OFFLINE_PLACEHOLDERS)?,
+            maintenance: Self::load("STATUS_TEMPLATE_MAINTENANCE", MAINTENANCE_PLACEHOLDERS)?,
+        })
+    }
+
+    fn load(var: &str, allowed: &[&str]) -> Result<Option<Template>, Box<dyn std::error::Error + Send + Sync>> {
+        let Ok(path) = env::var(var) else {
+            return Ok(None);
+        };
+        let source = std::fs::read_to_string(&path)
+            .map_err(|e| format!("{}: failed to read {}: {}", var, path, e))?;
+        let template = Template::parse(source.trim_end(), allowed)
+            .map_err(|e| format!("{}: {}: {}", var, path, e))?;
+        Ok(Some(template))
+    }
+}
 
 pub struct StatusMonitor {
     channel_id: ChannelId,
     display_ip: String,
     connect_ip: String,
     server_port: u16,
+    templates: StatusTemplates,
+    state: Arc<AppState>,
     last_message_id: Arc<RwLock<Option<MessageId>>>,
+    message_path: PathBuf,
+}
+
+/// 再起動後に前回のステータスメッセージを消せるよう、送ったメッセージのIDを保存する
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct StoredStatusMessage {
+    message_id: Option<MessageId>,
 }
 
 impl StatusMonitor {
-    pub fn new(channel_id: u64, display_ip: String, connect_ip: String, server_port: u16) -> Self {
+    /// STATUS_MESSAGE_PATH (既定は status_message.json) にステータスメッセージのIDを保存する
+    pub fn new(
+        channel_id: u64,
+        display_ip: String,
+        connect_ip: String,
+        server_port: u16,
+        templates: StatusTemplates,
+        state: Arc<AppState>,
+    ) -> Self {
         Self {
             channel_id: ChannelId::new(channel_id),
             display_ip,
             connect_ip,
             server_port,
+            templates,
+            state,
             last_message_id: Arc::new(RwLock::new(None)),
+            message_path: PathBuf::from(env::var("STATUS_MESSAGE_PATH").unwrap_or_else(|_| "status_message.json".to_string())),
         }
     }
 
@@ -47,24 +114,33 @@ impl StatusMonitor {
         });
     }
 
+    /// 前回起動したときに送ったステータスメッセージを消す (テンプレートで文面が変わっても見分けられるよう、保存したIDで探す)
     async fn cleanup_old_messages(&self, ctx: &Context) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         let bot_id = ctx.cache.current_user().id;
-        
-        if let Ok(messages) = self.channel_id.messages(&ctx.http, Default::default()).await {
-            for message in messages {
-                if message.author.id == bot_id && (message.content.contains("Minecraft Bedrock サーバー状態") || message.content.contains("Minecraft Bedrock Server Status")) {
-                    if let Err(e) = self.channel_id.delete_message(&ctx.http, message.id).await {
-                        eprintln!("Failed to delete old message {}: {}", message.id, e);
-                    } else {
-                        println!("Deleted old status message: {}", message.id);
-                    }
+        let stored: StoredStatusMessage = load_json(&self.message_path)?;
+        let Some(message_id) = stored.message_id else {
+            return Ok(());
+        };
+        // 消されていたり別のチャンネルのIDだったりしたら何もしない
+        if let Ok(message) = self.channel_id.message(&ctx.http, message_id).await {
+            if message.author.id == bot_id {
+                if let Err(e) = self.channel_id.delete_message(&ctx.http, message.id).await {
+                    eprintln!("Failed to delete old message {}: {}", message.id, e);
+                } else {
+                    println!("Deleted old status message: {}", message.id);
                 }
             }
         }
-        
+        self.remember(None);
         Ok(())
     }
 
+    fn remember(&self, message_id: Option<MessageId>) {
+        if let Err(e) = save_json(&self.message_path, &StoredStatusMessage { message_id }) {
+            eprintln!("Failed to save status message ID: {}", e);
+        }
+    }
+
     async fn update_loop(self: Arc<Self>, ctx: Arc<Context>) {
         loop {
             if let Err(e) = self.update_status(&ctx).await {
@@ -116,6 +192,7 @@ impl StatusMonitor {
                         .flags(MessageFlags::SUPPRESS_NOTIFICATIONS);
                     let new_msg = self.channel_id.send_message(&ctx.http, builder).await?;
                     *message_id = Some(new_msg.id);
+                    self.remember(*message_id);
                 }
             }
         } else {
@@ -124,6 +201,7 @@ impl StatusMonitor {
                 .flags(MessageFlags::SUPPRESS_NOTIFICATIONS);
             let msg = self.channel_id.send_message(&ctx.http, builder).await?;
             *message_id = Some(msg.id);
+            self.remember(*message_id);
         }
         
         Ok(())
@@ -142,6 +220,7 @@ impl StatusMonitor {
             .flags(MessageFlags::SUPPRESS_NOTIFICATIONS);
         let new_msg = self.channel_id.send_message(&ctx.http, builder).await?;
         *message_id = Some(new_msg.id);
+        self.remember(*message_id);
         
         Ok(())
     }
@@ -149,117 +228,165 @@ impl StatusMonitor {
     async fn get_server_status(&self) -> String {
         let now = Local::now();
         let timestamp = now.format("%H:%M").to_string();
-        let lang = env::var("LANGUAGE").unwrap_or_else(|_| "JP".to_string());
-        let is_en = lang.to_uppercase() == "EN";
-        
-        match self.ping_server().await {
-            Ok(info) => {
-                if is_en {
-                    format!(
-                        "**Minecraft Bedrock Server Status**\n\
-                        Server IP: `{}`\n\
-                        Port: `{}`\n\
-                        Status: 🟢 Online\n\
-                        Players: {}/{}\n\
-                        Last Updated: {}",
-                        self.display_ip, self.server_port,
-                        info.online_players, info.max_players,
-                        timestamp
-                    )
-                } else {
-                    format!(
-                        "**Minecraft Bedrock サーバー状態**\n\
-                        サーバーIP: `{}`\n\
-                        ポート: `{}`\n\
-                        サーバー状態: 🟢 オンライン\n\
-                        プレイヤー数: {}/{}\n\
-                        最終更新: {}",
-                        self.display_ip, self.server_port,
-                        info.online_players, info.max_players,
-                        timestamp
-                    )
-                }
+        let lang = Language::from_env();
+        let pending_restart = self.state.restart.pending();
+
+        let mut values = HashMap::from([
+            ("ip", self.display_ip.clone()),
+            ("port", self.server_port.to_string()),
+            ("time", timestamp),
+            (
+                "restart",
+                pending_restart.map(|at| format!("<t:{}:R>", at.timestamp())).unwrap_or_else(|| "-".to_string()),
+            ),
+        ]);
+        if let Some(info) = self.state.maintenance.current() {
+            values.insert("reason", info.reason.unwrap_or_else(|| "-".to_string()));
+            values.insert("eta", info.eta.map(|eta| format!("<t:{}:R>", eta.timestamp())).unwrap_or_else(|| "-".to_string()));
+            if let Some(template) = &self.templates.maintenance {
+                return template.render(&values);
             }
-            Err(_) => {
-                if is_en {
-                    format!(
-                        "**Minecraft Bedrock Server Status**\n\
-                        Server IP: `{}`\n\
-                        Port: `{}`\n\
-                        Status: 🔴 Offline\n\
-                        Last Updated: {}",
-                        self.display_ip, self.server_port,
-                        timestamp
-                    )
-                } else {
-                    format!(
-                        "**Minecraft Bedrock サーバー状態**\n\
-                        サーバーIP: `{}`\n\
-                        ポート: `{}`\n\
-                        サーバー状態: 🔴 オフライン\n\
-                        最終更新: {}",
-                        self.display_ip, self.server_port,
-                        timestamp
-                    )
+            return tr!(
+                lang,
+                "status-maintenance",
+                ip = values["ip"].as_str(),
+                port = values["port"].as_str(),
+                reason = values["reason"].as_str(),
+                eta = values["eta"].as_str(),
+                time = values["time"].as_str(),
+            );
+        }
+        let mut player_list = String::new();
+        let online = match ping_server(&self.connect_ip, self.server_port).await {
+            Ok(info) => {
+                values.insert("players", info.online_players.to_string());
+                values.insert("max", info.max_players.to_string());
+                let names: Vec<String> = self.state.players.online().await.into_iter().map(|p| p.name).collect();
+                if !names.is_empty() {
+                    player_list = format!(" ({})", names.join(", "));
                 }
+                values.insert("player_names", if names.is_empty() { "-".to_string() } else { names.join(", ") });
+                values.insert("motd", info.motd);
+                values.insert("version", info.version);
+                values.insert("latency", format!("{}ms", info.latency.as_millis()));
+                values.insert(
+                    "uptime",
+                    self.state.server_controller.uptime().map(format_duration).unwrap_or_else(|| "-".to_string()),
+                );
+                let usage = self.state.server_controller.resource_usage();
+                values.insert("cpu", usage.as_ref().map(ResourceMonitor::format_cpu).unwrap_or_else(|| "-".to_string()));
+                values.insert(
+                    "memory",
+                    usage.as_ref().map(|usage| self.state.resources.format_memory(usage)).unwrap_or_else(|| "-".to_string()),
+                );
+                true
             }
+            Err(_) => false,
+        };
+
+        let template = if online { &self.templates.online } else { &self.templates.offline };
+        if let Some(template) = template {
+            return template.render(&values);
+        }
+        let mut text = if online {
+            tr!(
+                lang,
+                "status-online",
+                ip = values["ip"].as_str(),
+                port = values["port"].as_str(),
+                players = values["players"].as_str(),
+                max = values["max"].as_str(),
+                player_list = player_list,
+                time = values["time"].as_str(),
+            )
+        } else {
+            tr!(
+                lang,
+                "status-offline",
+                ip = values["ip"].as_str(),
+                port = values["port"].as_str(),
+                time = values["time"].as_str(),
+            )
+        };
+        if online && self.state.server_controller.resource_usage().is_some() {
+            text.push('\n');
+            text.push_str(&tr!(
+                lang,
+                "status-resources",
+                cpu = values["cpu"].as_str(),
+                memory = values["memory"].as_str(),
+            ));
+        }
+        if pending_restart.is_some() {
+            text.push('\n');
+            text.push_str(&tr!(lang, "status-restart-pending", time = values["restart"].as_str()));
         }
+        text
     }
+}
 
-    async fn ping_server(&self) -> Result<ServerInfo, Box<dyn std::error::Error + Send + Sync>> {
-        let socket = UdpSocket::bind("0.0.0.0:0").await?;
-        socket.connect(format!("{}:{}", self.connect_ip, self.server_port)).await?;
+#[derive(Debug, Clone)]
+pub struct ServerInfo {
+    pub motd: String,
+    pub version: String,
+    pub online_players: i32,
+    pub max_players: i32,
+    pub latency: Duration,
+}
 
-        let mut packet = Vec::with_capacity(33);
-        packet.push(0x01);
-        
-        let valid_time = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as u64;
-        packet.write_u64::<BigEndian>(valid_time)?;
-        
-        let magic = [
-            0x00, 0xff, 0xff, 0x00, 0xfe, 0xfe, 0xfe, 0xfe, 0xfd, 0xfd, 0xfd, 0xfd, 0x12, 0x34, 0x56, 0x78
-        ];
-        packet.extend_from_slice(&magic);
-        
-        packet.write_u64::<BigEndian>(rand::random())?;
+/// RakNet の Unconnected Ping でサーバーの情報を取得する
+pub async fn ping_server(ip: &str, port: u16) -> Result<ServerInfo, Box<dyn std::error::Error + Send + Sync>> {
+    let socket = UdpSocket::bind("0.0.0.0:0").await?;
+    socket.connect(format!("{}:{}", ip, port)).await?;
 
-        socket.send(&packet).await?;
+    let mut packet = Vec::with_capacity(33);
+    packet.push(0x01);
+    
+    let valid_time = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as u64;
+    packet.write_u64::<BigEndian>(valid_time)?;
+    
+    let magic = [
+        0x00, 0xff, 0xff, 0x00, 0xfe, 0xfe, 0xfe, 0xfe, 0xfd, 0xfd, 0xfd, 0xfd, 0x12, 0x34, 0x56, 0x78
+    ];
+    packet.extend_from_slice(&magic);
+    
+    packet.write_u64::<BigEndian>(rand::random())?;
 
-        let mut buf = [0u8; 1024];
-        let result = timeout(Duration::from_secs(2), socket.recv(&mut buf)).await?;
-        let len = result?;
+    let sent_at = Instant::now();
+    socket.send(&packet).await?;
 
-        if len > 0 && buf[0] == 0x1c {
-            let mut cursor = Cursor::new(&buf[1..len]);
-            if cursor.position() + 32 <= len as u64 {
-                cursor.set_position(cursor.position() + 32);
-                
-                let magic_idx = buf.windows(16).position(|window| window == magic);
-                if let Some(idx) = magic_idx {
-                    let string_start = idx + 16 + 2;
-                    if string_start < len {
-                        let data_str = String::from_utf8_lossy(&buf[string_start..len]);
-                        let parts: Vec<&str> = data_str.split(';').collect();
-                        
-                        if parts.len() >= 6 {
-                            if let (Ok(online), Ok(max)) = (parts[4].parse::<i32>(), parts[5].parse::<i32>()) {
-                                return Ok(ServerInfo {
-                                    online_players: online,
-                                    max_players: max,
-                                });
-                            }
+    let mut buf = [0u8; 1024];
+    let result = timeout(Duration::from_secs(2), socket.recv(&mut buf)).await?;
+    let len = result?;
+    let latency = sent_at.elapsed();
+
+    if len > 0 && buf[0] == 0x1c {
+        let mut cursor = Cursor::new(&buf[1..len]);
+        if cursor.position() + 32 <= len as u64 {
+            cursor.set_position(cursor.position() + 32);
+            
+            let magic_idx = buf.windows(16).position(|window| window == magic);
+            if let Some(idx) = magic_idx {
+                let string_start = idx + 16 + 2;
+                if string_start < len {
+                    let data_str = String::from_utf8_lossy(&buf[string_start..len]);
+                    let parts: Vec<&str> = data_str.split(';').collect();
+                    
+                    if parts.len() >= 6 {
+                        if let (Ok(online), Ok(max)) = (parts[4].parse::<i32>(), parts[5].parse::<i32>()) {
+                            return Ok(ServerInfo {
+                                motd: parts[1].to_string(),
+                                version: parts[3].to_string(),
+                                online_players: online,
+                                max_players: max,
+                                latency,
+                            });
                         }
                     }
                 }
             }
         }
-
-        Err("Failed to ping server".into())
     }
-}
 
-#[derive(Debug, Clone)]
-struct ServerInfo {
-    online_players: i32,
-    max_players: i32,
+    Err("Failed to ping server".into())
 }
@@ -0,0 +1,25 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::Path;
+
+/// JSONファイルを読み込む。ファイルがなければ既定値を返す
+pub fn load_json<T: DeserializeOwned + Default>(path: &Path) -> Result<T, Box<dyn std::error::Error + Send + Sync>> {
+    match std::fs::read_to_string(path) {
+        Ok(text) => Ok(serde_json::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e))?),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(T::default()),
+        Err(e) => Err(format!("{}: {}", path.display(), e).into()),
+    }
+}
+
+/// 一時ファイルに書いてから置き換えることで、書き込み途中の壊れたファイルが残らないようにする
+pub fn save_json<T: Serialize>(path: &Path, value: &T) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    write_atomic(path, serde_json::to_string_pretty(value)?.as_bytes())
+}
+
+pub fn write_atomic(path: &Path, contents: &[u8]) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    std::fs::write(&tmp, contents)?;
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+use std::fmt;
+
+/// `{name}` 形式のプレースホルダーを持つ簡単なテンプレート。`{{` と `}}` で波括弧そのものを書ける
+#[derive(Debug, Clone)]
+pub struct Template {
+    parts: Vec<Part>,
+}
+
+#[derive(Debug, Clone)]
+enum Part {
+    Text(String),
+    Placeholder(String),
+}
+
+#[derive(Debug)]
+pub enum TemplateError {
+    Unclosed { position: usize },
+    UnexpectedClose { position: usize },
+    Unknown { name: String, position: usize },
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TemplateError::Unclosed { position } => write!(f, "unclosed '{{' at position {}", position),
+            TemplateError::UnexpectedClose { position } => write!(f, "unexpected '}}' at position {} (use '}}}}' for a literal brace)", position),
+            TemplateError::Unknown { name, position } => write!(f, "unknown placeholder '{{{}}}' at position {}", name, position),
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+impl Template {
+    /// `allowed` に含まれないプレースホルダーはエラーにする
+    pub fn parse(source: &str, allowed: &[&str]) -> Result<Self, TemplateError> {
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        let mut chars = source.char_indices().peekable();
+
+        while let Some((position, c)) = chars.next() {
+            match c {
+                '{' if matches!(chars.peek(), Some((_, '{'))) => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if matches!(chars.peek(), Some((_, '}'))) => {
+                    chars.next();
+                    text.push('}');
+                }
+                '{' => {
+                    let mut name = String::new();
+                    loop {
+                        match chars.next() {
+                            Some((_, '}')) => break,
+                            Some((_, c)) => name.push(c),
+                            None => return Err(TemplateError::Unclosed { position }),
+                        }
+                    }
+                    let name = name.trim().to_string();
+                    if !allowed.contains(&name.as_str()) {
+                        return Err(TemplateError::Unknown { name, position });
+                    }
+                    if !text.is_empty() {
+                        parts.push(Part::Text(std::mem::take(&mut text)));
+                    }
+                    parts.push(Part::Placeholder(name));
+                }
+                '}' => return Err(TemplateError::UnexpectedClose { position }),
+                c => text.push(c),
+            }
+        }
+        if !text.is_empty() {
+            parts.push(Part::Text(text));
+        }
+        Ok(Self { parts })
+    }
+
+    pub fn render(&self, values: &HashMap<&str, String>) -> String {
+        self.parts
+            .iter()
+            .map(|part| match part {
+                Part::Text(text) => text.as_str(),
+                Part::Placeholder(name) => values.get(name.as_str()).map(String::as_str).unwrap_or(""),
+            })
+            .collect()
+    }
+}
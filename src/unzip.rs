@@ -0,0 +1,42 @@
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::Path;
+use zip::read::ZipFile;
+
+/// zip を展開するときのファイル数と合計サイズ (展開後) の残り
+pub struct UnzipLimit {
+    entries: usize,
+    bytes: u64,
+}
+
+impl UnzipLimit {
+    pub fn new(max_entries: usize, max_bytes: u64) -> Self {
+        Self { entries: max_entries, bytes: max_bytes }
+    }
+
+    fn take_entry(&mut self, file: &ZipFile) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if self.entries == 0 {
+            return Err("too many files in the archive".into());
+        }
+        self.entries -= 1;
+        if file.size() > self.bytes {
+            return Err("archive is too large when extracted".into());
+        }
+        Ok(())
+    }
+
+    /// ファイルを1つ `path` に書き出す。メモリには溜めず、書き出した量で上限を確かめる
+    pub fn extract(&mut self, file: &mut ZipFile, path: &Path) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.take_entry(file)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut out = File::create(path)?;
+        let written = io::copy(&mut file.by_ref().take(self.bytes + 1), &mut out)?;
+        if written > self.bytes {
+            return Err("archive is too large when extracted".into());
+        }
+        self.bytes -= written;
+        Ok(())
+    }
+}
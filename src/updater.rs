@@ -0,0 +1,394 @@
+use crate::audit::{AuditEntry, AuditLog};
+use crate::backup::BackupManager;
+use crate::lifecycle::Lifecycle;
+use crate::schedule::{next_run, schedule_from_env, sleep_until};
+use crate::server_controller::{ServerController, ServerState};
+use crate::status::ping_server;
+use chrono::{Local, Utc};
+use cron::Schedule;
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// 配布 zip の最上位にあるはずの実行ファイル
+const EXECUTABLES: [&str; 2] = ["bedrock_server.exe", "bedrock_server"];
+/// 更新で上書きしないファイルとフォルダ (すでにある場合だけ)
+const PRESERVED: [&str; 4] = ["server.properties", "allowlist.json", "permissions.json", "worlds"];
+/// 公式のダウンロードURLに含まれるプラットフォーム名
+const PLATFORM: &str = if cfg!(windows) { "bin-win" } else { "bin-linux" };
+/// 接続までとフィードの取得、zip のダウンロード全体の制限時間
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+const FEED_TIMEOUT: Duration = Duration::from_secs(60);
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+
+fn url_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r#"https?://[^"'\s<>]+"#).unwrap())
+}
+
+fn zip_name_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"bedrock-server-(\d+(?:\.\d+)+)\.zip").unwrap())
+}
+
+/// 更新に使う zip の場所
+#[derive(Debug, Clone)]
+pub enum UpdateSource {
+    Url(String),
+    File(PathBuf),
+}
+
+impl UpdateSource {
+    pub fn parse(text: &str) -> Self {
+        let text = text.trim();
+        if text.starts_with("http://") || text.starts_with("https://") {
+            UpdateSource::Url(text.to_string())
+        } else {
+            UpdateSource::File(PathBuf::from(text))
+        }
+    }
+
+    /// ファイル名 (bedrock-server-1.21.50.10.zip) から分かるバージョン
+    fn version(&self) -> Option<String> {
+        let name = match self {
+            UpdateSource::Url(url) => url.as_str(),
+            UpdateSource::File(path) => path.to_str()?,
+        };
+        zip_name_pattern().captures(name).map(|caps| caps[1].to_string())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Release {
+    pub version: String,
+    /// フィードにダウンロードURLが載っていればそのURL
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct UpdateCheck {
+    /// サーバーの応答から分かる動作中のバージョン。止まっていれば None
+    pub current: Option<String>,
+    pub latest: Release,
+}
+
+impl UpdateCheck {
+    pub fn is_update_available(&self) -> bool {
+        self.current.as_deref().is_none_or(|current| is_newer(&self.latest.version, current))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct UpdateResult {
+    pub version: Option<String>,
+    /// 置き換えたファイルの数
+    pub files: usize,
+    /// 置き換える前のファイルをまとめた zip
+    pub backup: PathBuf,
+}
+
+/// Bedrock Dedicated Server を配布 zip から更新する
+pub struct Updater {
+    server_controller: Arc<ServerController>,
+    lifecycle: Arc<Lifecycle>,
+    backups: Arc<BackupManager>,
+    audit: Arc<AuditLog>,
+    feed_url: Option<String>,
+    download_url: Option<String>,
+    schedule: Option<Schedule>,
+    ping_ip: String,
+    ping_port: u16,
+    client: reqwest::Client,
+    // 同時に2つの更新をしない
+    running: tokio::sync::Mutex<()>,
+}
+
+impl Updater {
+    /// BDS_VERSION_FEED に最新版を調べるURL (公式のダウンロードリンクのJSONや、バージョンだけを書いたテキスト)、
+    /// BDS_DOWNLOAD_URL に zip のURL (`{version}` は最新版のバージョンに置き換える)、
+    /// UPDATE_SCHEDULE に cron 形式で自動更新の時刻を指定できる
+    pub fn from_env(
+        server_controller: Arc<ServerController>,
+        lifecycle: Arc<Lifecycle>,
+        backups: Arc<BackupManager>,
+        audit: Arc<AuditLog>,
+        ping_ip: String,
+        ping_port: u16,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let optional = |var: &str| env::var(var).ok().map(|value| value.trim().to_string()).filter(|value| !value.is_empty());
+        let client = reqwest::Client::builder()
+            .user_agent(concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION")))
+            .connect_timeout(CONNECT_TIMEOUT)
+            .timeout(DOWNLOAD_TIMEOUT)
+            .build()?;
+        Ok(Self {
+            server_controller,
+            lifecycle,
+            backups,
+            audit,
+            feed_url: optional("BDS_VERSION_FEED"),
+            download_url: optional("BDS_DOWNLOAD_URL"),
+            schedule: schedule_from_env("UPDATE_SCHEDULE")?,
+            ping_ip,
+            ping_port,
+            client,
+            running: tokio::sync::Mutex::new(()),
+        })
+    }
+
+    async fn latest(&self) -> Result<Release, Box<dyn std::error::Error + Send + Sync>> {
+        let feed_url = self.feed_url.as_deref().ok_or("BDS_VERSION_FEED is not set")?;
+        let body = self.client.get(feed_url).timeout(FEED_TIMEOUT).send().await?.error_for_status()?.text().await?;
+        parse_feed(&body).ok_or_else(|| format!("no Bedrock server version found in {}", feed_url).into())
+    }
+
+    /// 動作中のバージョンとフィードの最新版を比べる
+    pub async fn check(&self) -> Result<UpdateCheck, Box<dyn std::error::Error + Send + Sync>> {
+        let current = ping_server(&self.ping_ip, self.ping_port).await.ok().map(|info| info.version);
+        Ok(UpdateCheck { current, latest: self.latest().await? })
+    }
+
+    /// `source` が None ならフィードの最新版を使う
+    async fn resolve_source(&self, source: Option<UpdateSource>) -> Result<UpdateSource, Box<dyn std::error::Error + Send + Sync>> {
+        if let Some(source) = source {
+            return Ok(source);
+        }
+        let latest = self.latest().await?;
+        if let Some(url) = latest.url {
+            return Ok(UpdateSource::Url(url));
+        }
+        let template = self
+            .download_url
+            .as_deref()
+            .ok_or("the feed has no download URL and BDS_DOWNLOAD_URL is not set")?;
+        Ok(UpdateSource::Url(template.replace("{version}", &latest.version)))
+    }
+
+    async fn download(&self, url: &str, path: &Path) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        println!("Downloading {}", url);
+        let mut response = self.client.get(url).send().await?.error_for_status()?;
+        let mut file = File::create(path)?;
+        while let Some(chunk) = response.chunk().await? {
+            file.write_all(&chunk)?;
+        }
+        file.sync_all()?;
+        Ok(())
+    }
+
+    /// ワールドをバックアップし、サーバーを止めて配布 zip で置き換えてから起動し直す。
+    /// 設定ファイルと worlds はそのまま残す。止まっていたサーバーは止めたままにする
+    pub async fn install(&self, source: Option<UpdateSource>) -> Result<UpdateResult, Box<dyn std::error::Error + Send + Sync>> {
+        let _running = self.running.lock().await;
+        let source = self.resolve_source(source).await?;
+        let version = source.version();
+        let id = Local::now().format("%Y%m%d-%H%M%S").to_string();
+        let (archive, downloaded) = match &source {
+            UpdateSource::Url(url) => {
+                let path = env::temp_dir().join(format!("bds-update-{}.zip", id));
+                if let Err(e) = self.download(url, &path).await {
+                    let _ = fs::remove_file(&path);
+                    return Err(format!("download failed: {}", e).into());
+                }
+                (path, true)
+            }
+            UpdateSource::File(path) => (path.clone(), false),
+        };
+        let result = self.install_archive(&archive, &id).await;
+        if downloaded {
+            let _ = fs::remove_file(&archive);
+        }
+        let (files, backup) = result?;
+        Ok(UpdateResult { version, files, backup })
+    }
+
+    async fn install_archive(&self, archive: &Path, id: &str) -> Result<(usize, PathBuf), Box<dyn std::error::Error + Send + Sync>> {
+        let path = archive.to_path_buf();
+        tokio::task::spawn_blocking(move || validate_zip(&path)).await??;
+
+        let was_running = self.server_controller.state() != ServerState::Stopped;
+        if was_running {
+            self.backups.create().await.map_err(|e| format!("world backup failed: {}", e))?;
+            self.lifecycle.stop().await?;
+        }
+        let server_dir = self.server_controller.server_dir();
+        let backup = self.backups.backup_dir().join("install").join(format!("bds-{}.zip", id));
+        let (path, backup_path) = (archive.to_path_buf(), backup.clone());
+        let result = tokio::task::spawn_blocking(move || install_zip(&path, &server_dir, &backup_path))
+            .await
+            .map_err(|e| e.into())
+            .and_then(|result| result);
+        // 置き換えに失敗しても、止める前に動いていたなら起動し直す
+        if was_running {
+            if let Err(e) = self.lifecycle.start().await {
+                eprintln!("Failed to start the server after the update: {}", e);
+                if result.is_ok() {
+                    return Err(format!("updated, but the server did not start: {}", e).into());
+                }
+            }
+        }
+        let files = result?;
+        println!("Updated {} files (previous files saved to {:?})", files, backup);
+        Ok((files, backup))
+    }
+
+    /// UPDATE_SCHEDULE の時刻に最新版を確認し、新しければ更新する
+    pub fn start_schedule(self: &Arc<Self>) {
+        let Some(schedule) = self.schedule.clone() else { return };
+        let updater = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut after = Utc::now();
+            while let Some(next) = next_run(&schedule, after) {
+                sleep_until(next).await;
+                after = next;
+                let check = match updater.check().await {
+                    Ok(check) => check,
+                    Err(e) => {
+                        eprintln!("Update check failed: {}", e);
+                        continue;
+                    }
+                };
+                // 止まっているサーバーのバージョンは分からないので、自動では更新しない
+                if check.current.is_none() || !check.is_update_available() {
+                    continue;
+                }
+                let arguments = BTreeMap::from([("version".to_string(), check.latest.version.clone())]);
+                let result = updater.install(None).await;
+                if let Err(e) = &result {
+                    eprintln!("Scheduled update failed: {}", e);
+                }
+                updater.audit.record(AuditEntry::new(
+                    None,
+                    "update install",
+                    arguments,
+                    result
+                        .map(|result| format!("updated {} files", result.files))
+                        .map_err(|e| e.to_string()),
+                ));
+            }
+        });
+    }
+}
+
+/// 公式のダウンロードリンク (このOS用の bedrock-server-<version>.zip) か、
+/// 1行目にバージョンだけが書かれたテキストを読む
+fn parse_feed(body: &str) -> Option<Release> {
+    let platform = format!("/{}/", PLATFORM);
+    let url = url_pattern()
+        .find_iter(body)
+        .map(|url| url.as_str())
+        .find(|url| url.contains(&platform) && zip_name_pattern().is_match(url));
+    if let Some(url) = url {
+        let version = zip_name_pattern().captures(url)?[1].to_string();
+        return Some(Release { version, url: Some(url.to_string()) });
+    }
+    let version = body.lines().next()?.trim();
+    let is_version = !version.is_empty() && version.split('.').all(|part| part.parse::<u32>().is_ok());
+    is_version.then(|| Release { version: version.to_string(), url: None })
+}
+
+/// サーバーは "1.21.50" のように短く答えるので、短い方の桁数までを比べる
+fn is_newer(latest: &str, current: &str) -> bool {
+    let parse = |version: &str| -> Vec<u32> { version.split('.').map(|part| part.trim().parse().unwrap_or(0)).collect() };
+    let (latest, current) = (parse(latest), parse(current));
+    latest.iter().zip(&current).find(|(a, b)| a != b).is_some_and(|(a, b)| a > b)
+}
+
+fn validate_zip(path: &Path) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let archive = ZipArchive::new(File::open(path).map_err(|e| format!("{}: {}", path.display(), e))?)?;
+    if !archive.file_names().any(|name| EXECUTABLES.contains(&name)) {
+        return Err("the zip does not look like a Bedrock Dedicated Server (no bedrock_server at the top level)".into());
+    }
+    Ok(())
+}
+
+/// 残すべき設定ファイルと worlds か
+fn is_preserved(server_dir: &Path, name: &Path) -> bool {
+    match name.components().next() {
+        Some(Component::Normal(first)) => {
+            PRESERVED.iter().any(|preserved| first == *preserved) && server_dir.join(first).exists()
+        }
+        _ => false,
+    }
+}
+
+/// 上書きするファイルを `backup` にまとめてから zip の中身で置き換え、置き換えたファイルの数を返す。
+/// 途中で失敗したら `backup` から元に戻し、新しく作ったファイルを消す
+fn install_zip(archive: &Path, server_dir: &Path, backup: &Path) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+    let mut archive = ZipArchive::new(File::open(archive)?)?;
+    let mut targets = Vec::new();
+    for i in 0..archive.len() {
+        let file = archive.by_index(i)?;
+        let name = file.enclosed_name().ok_or_else(|| format!("unsafe path in update: {}", file.name()))?;
+        if !file.is_dir() && !is_preserved(server_dir, &name) {
+            targets.push((i, name));
+        }
+    }
+
+    fs::create_dir_all(backup.parent().unwrap_or(Path::new(".")))?;
+    let mut zip = ZipWriter::new(File::create(backup)?);
+    let options = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .large_file(true);
+    let mut created = Vec::new();
+    for (_, name) in &targets {
+        let path = server_dir.join(name);
+        if !path.is_file() {
+            created.push(path);
+            continue;
+        }
+        zip.start_file(name.to_string_lossy().replace('\\', "/"), options)?;
+        io::copy(&mut File::open(&path)?, &mut zip)?;
+    }
+    zip.finish()?.sync_all()?;
+
+    if let Err(e) = write_files(&mut archive, &targets, server_dir) {
+        return Err(match restore_files(backup, server_dir, &created) {
+            Ok(()) => format!("{} (the previous files were restored)", e).into(),
+            Err(restore) => format!("{}; restoring the previous files from {} also failed: {}", e, backup.display(), restore).into(),
+        });
+    }
+    Ok(targets.len())
+}
+
+fn write_files(
+    archive: &mut ZipArchive<File>,
+    targets: &[(usize, PathBuf)],
+    server_dir: &Path,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    for (i, name) in targets {
+        let mut file = archive.by_index(*i)?;
+        let path = server_dir.join(name);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        io::copy(&mut file, &mut File::create(&path).map_err(|e| format!("{}: {}", path.display(), e))?)?;
+        #[cfg(unix)]
+        if let Some(mode) = file.unix_mode() {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&path, fs::Permissions::from_mode(mode))?;
+        }
+    }
+    Ok(())
+}
+
+/// install_zip の前に保存したファイルを書き戻す
+fn restore_files(backup: &Path, server_dir: &Path, created: &[PathBuf]) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let mut archive = ZipArchive::new(File::open(backup)?)?;
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i)?;
+        let name = file.enclosed_name().ok_or_else(|| format!("unsafe path in backup: {}", file.name()))?;
+        io::copy(&mut file, &mut File::create(server_dir.join(name))?)?;
+    }
+    for path in created {
+        let _ = fs::remove_file(path);
+    }
+    println!("Restored the server files from {:?}", backup);
+    Ok(())
+}
@@ -0,0 +1,209 @@
+use crate::lifecycle::Lifecycle;
+use crate::properties::ServerConfig;
+use crate::server_controller::{ServerController, ServerState};
+use crate::unzip::UnzipLimit;
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use zip::ZipArchive;
+
+#[derive(Debug, Clone)]
+pub struct World {
+    /// worlds の中のフォルダ名 (level-name に指定する名前)
+    pub name: String,
+    /// levelname.txt に書かれたゲーム内の名前
+    pub display_name: Option<String>,
+    pub size: u64,
+    /// level-name に設定されている
+    pub active: bool,
+}
+
+/// `/world switch` の結果
+#[derive(Debug, Clone)]
+pub struct WorldSwitch {
+    pub previous: String,
+    /// サーバーが動いていたので再起動した
+    pub restarted: bool,
+}
+
+/// フォルダの中のファイルの合計サイズ
+fn dir_size(dir: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(dir) else { return 0 };
+    entries
+        .filter_map(Result::ok)
+        .map(|entry| match entry.file_type() {
+            Ok(kind) if kind.is_dir() => dir_size(&entry.path()),
+            Ok(_) => entry.metadata().map(|metadata| metadata.len()).unwrap_or(0),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+/// level-name にそのまま使えるフォルダ名か。
+/// 展開中 (`.restore-*`, `*.partial`) や復元前の退避 (`*.pre-restore-*`) のフォルダは含めない
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 64
+        && !name.starts_with('.')
+        && !name.ends_with(".partial")
+        && !name.contains(".pre-restore-")
+        && !name.chars().any(|c| c.is_control() || matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|'))
+}
+
+/// 受け付ける .mcworld の大きさ
+pub const MAX_UPLOAD_SIZE: u64 = 500 << 20;
+/// 展開後のファイル数と合計サイズの上限
+const MAX_ENTRIES: usize = 20_000;
+const MAX_UNPACKED_SIZE: u64 = 2 << 30;
+
+/// .mcworld (level.dat を含む zip) を `dir` に展開し、level.dat のあるフォルダを返す。
+/// level.dat が1つ下のフォルダにある zip も受け付ける
+fn extract_world(bytes: &[u8], dir: &Path) -> Result<PathBuf, Box<dyn std::error::Error + Send + Sync>> {
+    let mut archive = ZipArchive::new(Cursor::new(bytes))?;
+    let mut limit = UnzipLimit::new(MAX_ENTRIES, MAX_UNPACKED_SIZE);
+    let mut root: Option<PathBuf> = None;
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i)?;
+        if file.is_dir() {
+            continue;
+        }
+        let name = file.enclosed_name().ok_or_else(|| format!("unsafe path in world: {}", file.name()))?;
+        if name.file_name().is_some_and(|file_name| file_name == "level.dat") {
+            let parent = name.parent().unwrap_or(Path::new("")).to_path_buf();
+            if root.as_ref().is_none_or(|root| parent.components().count() < root.components().count()) {
+                root = Some(parent);
+            }
+        }
+        limit.extract(&mut file, &dir.join(&name))?;
+    }
+    let root = root.ok_or("level.dat not found in the world file")?;
+    if root.components().count() > 1 {
+        return Err("level.dat must be at the top of the world file".into());
+    }
+    Ok(if root.as_os_str().is_empty() { dir.to_path_buf() } else { dir.join(root) })
+}
+
+/// worlds フォルダのワールドの一覧・切り替え・追加
+pub struct Worlds {
+    worlds_dir: PathBuf,
+    server_controller: Arc<ServerController>,
+    lifecycle: Arc<Lifecycle>,
+    config: Arc<ServerConfig>,
+    // 切り替えと再起動を同時に実行しない
+    switching: tokio::sync::Mutex<()>,
+}
+
+impl Worlds {
+    pub fn from_env(server_controller: Arc<ServerController>, lifecycle: Arc<Lifecycle>, config: Arc<ServerConfig>) -> Self {
+        Self {
+            worlds_dir: server_controller.worlds_dir(),
+            server_controller,
+            lifecycle,
+            config,
+            switching: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    /// level.dat のあるフォルダを名前順に返す
+    pub fn list(&self) -> Result<Vec<World>, Box<dyn std::error::Error + Send + Sync>> {
+        let level_name = self.config.level_name()?;
+        let entries = fs::read_dir(&self.worlds_dir).map_err(|e| format!("{}: {}", self.worlds_dir.display(), e))?;
+        let mut worlds: Vec<World> = entries
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                let name = path.file_name()?.to_str()?.to_string();
+                if !is_valid_name(&name) || !path.join("level.dat").is_file() {
+                    return None;
+                }
+                let display_name = fs::read_to_string(path.join("levelname.txt"))
+                    .ok()
+                    .map(|text| text.trim().to_string())
+                    .filter(|text| !text.is_empty() && *text != name);
+                Some(World {
+                    active: name == level_name,
+                    size: dir_size(&path),
+                    name,
+                    display_name,
+                })
+            })
+            .collect();
+        worlds.sort_by_key(|world| world.name.to_lowercase());
+        Ok(worlds)
+    }
+
+    /// level-name を書き換え、サーバーが動いていれば再起動して読み込み直す。
+    /// 再起動に失敗したら level-name を元に戻す
+    pub async fn switch(&self, name: &str) -> Result<WorldSwitch, Box<dyn std::error::Error + Send + Sync>> {
+        let _switching = self.switching.lock().await;
+        if !is_valid_name(name) || !self.worlds_dir.join(name).join("level.dat").is_file() {
+            return Err(format!("world not found: {}", name).into());
+        }
+        let previous = self.config.level_name()?;
+        if previous == name {
+            return Err(format!("{} is already the current world", name).into());
+        }
+        self.config.set("level-name", name)?;
+        println!("Switched level-name from {} to {}", previous, name);
+        let restarted = self.server_controller.state() != ServerState::Stopped;
+        if restarted {
+            if let Err(e) = self.lifecycle.restart().await {
+                match self.config.set("level-name", &previous) {
+                    Ok(_) => println!("Restored level-name to {} after the restart failed", previous),
+                    Err(rollback) => eprintln!("Failed to restore level-name to {}: {}", previous, rollback),
+                }
+                return Err(e);
+            }
+        }
+        Ok(WorldSwitch { previous, restarted })
+    }
+
+    /// .mcworld を新しいワールドとして展開する。`name` がなければ levelname.txt かファイル名から決める
+    pub fn upload(&self, file_name: &str, bytes: &[u8], name: Option<&str>) -> Result<World, Box<dyn std::error::Error + Send + Sync>> {
+        let path = Path::new(file_name);
+        let extension = path.extension().map(|ext| ext.to_string_lossy().to_lowercase());
+        if !matches!(extension.as_deref(), Some("mcworld" | "zip")) {
+            return Err(format!("{} is not a .mcworld file", file_name).into());
+        }
+        // 名前は levelname.txt を読むまで決まらないので、いったん隠しフォルダに展開する
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos();
+        let staging = self.worlds_dir.join(format!(".upload-{}", nanos));
+        let result = self.install_extracted(path, bytes, name, &staging);
+        let _ = fs::remove_dir_all(&staging);
+        result
+    }
+
+    fn install_extracted(
+        &self,
+        path: &Path,
+        bytes: &[u8],
+        name: Option<&str>,
+        staging: &Path,
+    ) -> Result<World, Box<dyn std::error::Error + Send + Sync>> {
+        let root = extract_world(bytes, staging)?;
+        let display_name = fs::read_to_string(root.join("levelname.txt"))
+            .ok()
+            .map(|text| text.trim().to_string())
+            .filter(|text| !text.is_empty());
+        let name = match name {
+            Some(name) => name.trim().to_string(),
+            None => display_name
+                .clone()
+                .or_else(|| path.file_stem().map(|stem| stem.to_string_lossy().to_string()))
+                .unwrap_or_default(),
+        };
+        if !is_valid_name(&name) {
+            return Err(format!("invalid world name: {}", name).into());
+        }
+        let target = self.worlds_dir.join(&name);
+        if target.exists() {
+            return Err(format!("a world named {} already exists", name).into());
+        }
+        fs::rename(&root, &target)?;
+        let size = dir_size(&target);
+        println!("Added world {} ({} bytes) to {:?}", name, size, target);
+        let display_name = display_name.filter(|display_name| *display_name != name);
+        Ok(World { name, display_name, size, active: false })
+    }
+}